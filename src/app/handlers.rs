@@ -2,6 +2,9 @@ use super::MyApp;
 use crate::music::{MusicTreeNode, TrackInfo};
 use crate::player::PlaybackState;
 
+/// キーボードショートカットによる音量の増減幅
+const VOLUME_STEP: f32 = 0.05;
+
 impl MyApp {
     pub fn handle_keyboard_shortcuts(&mut self, ctx: &eframe::egui::Context) {
         if ctx.input(|i| i.key_pressed(eframe::egui::Key::F) && i.modifiers.ctrl) {
@@ -24,8 +27,20 @@ impl MyApp {
             }
         }
         
+        // Lyrics sync editor: Enter stamps the current position, Ctrl+Z undoes
+        if self.lyrics_state.is_editing() && !self.lyrics_state.is_text_input() && !self.selection_state.search_has_focus {
+            if ctx.input(|i| i.key_pressed(eframe::egui::Key::Enter)) {
+                self.handle_lyrics_tap();
+            }
+            if ctx.input(|i| i.key_pressed(eframe::egui::Key::Z) && i.modifiers.ctrl) {
+                if let Some(editor) = self.lyrics_state.editor.as_mut() {
+                    editor.undo();
+                }
+            }
+        }
+        
         // Global playback shortcuts (disabled when search has focus or edit mode is active)
-        if !self.selection_state.search_has_focus && !self.seek_point_edit_state.is_editing {
+        if !self.selection_state.search_has_focus && !self.seek_point_edit_state.is_editing && !self.lyrics_state.is_text_input() {
             // Space: Play/Pause
             if ctx.input(|i| i.key_pressed(eframe::egui::Key::Space)) {
                 self.handle_play_pause();
@@ -50,6 +65,124 @@ impl MyApp {
             if ctx.input(|i| i.key_pressed(eframe::egui::Key::P) && i.modifiers.shift) {
                 self.handle_seek_forward();
             }
+            
+            // Ctrl+↑/Ctrl+↓: Volume up/down, M: Mute
+            if ctx.input(|i| i.key_pressed(eframe::egui::Key::ArrowUp) && i.modifiers.ctrl) {
+                self.handle_adjust_volume(VOLUME_STEP);
+            }
+            if ctx.input(|i| i.key_pressed(eframe::egui::Key::ArrowDown) && i.modifiers.ctrl) {
+                self.handle_adjust_volume(-VOLUME_STEP);
+            }
+            // 文字入力中（プレイリスト名の編集など）は無効
+            if !ctx.wants_keyboard_input() && ctx.input(|i| i.key_pressed(eframe::egui::Key::M) && i.modifiers.is_none()) {
+                self.handle_toggle_mute();
+            }
+            
+            // Ctrl+]: Lyrics earlier / Ctrl+[: Lyrics later
+            if ctx.input(|i| i.key_pressed(eframe::egui::Key::CloseBracket) && i.modifiers.ctrl) {
+                self.handle_lyrics_offset_adjust(100);
+            }
+            if ctx.input(|i| i.key_pressed(eframe::egui::Key::OpenBracket) && i.modifiers.ctrl) {
+                self.handle_lyrics_offset_adjust(-100);
+            }
+        }
+    }
+
+    /// 歌詞タイミング編集: 次の行に現在の再生位置を打刻
+    pub fn handle_lyrics_tap(&mut self) {
+        let is_editing_track_playing = match (self.lyrics_state.editor.as_ref(), self.playlist_manager.get_current_track()) {
+            (Some(editor), Some(current)) => editor.track_path() == current.path.as_path(),
+            _ => false,
+        };
+        if !is_editing_track_playing || *self.player_state.audio_player.get_state() == PlaybackState::Stopped {
+            return;
+        }
+
+        // 表示時に加算される楽曲ごとの補正を打刻位置にも反映
+        let position_ms = self.player_state.audio_player.get_playback_position().as_millis() as u64;
+        if let Some(editor) = self.lyrics_state.editor.as_mut() {
+            let user_offset_ms = self.lyrics_state.offset_manager.get_offset(editor.track_path());
+            editor.stamp_next((position_ms as i64 + user_offset_ms).max(0) as u64);
+        }
+    }
+
+    /// 歌詞タイミング編集: LRCファイルに保存して編集を終了
+    pub fn save_lyrics_edits(&mut self) {
+        let editor = match self.lyrics_state.editor.as_ref() {
+            Some(editor) => editor,
+            None => return,
+        };
+        if !editor.can_save() {
+            self.lyrics_state.edit_error = Some(format!("タイミング未設定の行が {} 行あります。全ての行を打刻してから保存してください", editor.unstamped_count()));
+            return;
+        }
+
+        match crate::lyrics::LrcWriter::save_for_track(editor.track_path(), &editor.to_lyrics()) {
+            Ok(_) => {
+                self.lyrics_state.stop_editing();
+                self.lyrics_state.reload();
+            }
+            Err(e) => {
+                self.lyrics_state.edit_error = Some(format!("保存に失敗しました: {}", e));
+            }
+        }
+    }
+
+    /// 歌詞を表示・補正する楽曲（再生中の楽曲を優先し、なければ選択楽曲）
+    pub fn lyrics_target_track(&self) -> Option<TrackInfo> {
+        self.playlist_manager.get_current_track()
+            .or(self.selection_state.selected_track.as_ref())
+            .cloned()
+    }
+
+    /// 歌詞の対象楽曲の歌詞を読み込み、その楽曲のパスを返す（歌詞タブが表示されていなくても対象楽曲に合わせる）
+    fn ensure_lyrics_target_loaded(&mut self) -> Option<std::path::PathBuf> {
+        let track = self.lyrics_target_track()?;
+        self.lyrics_state.ensure_loaded(&track.path);
+        Some(track.path)
+    }
+
+    /// 歌詞の対象楽曲のタイミング補正を増減して保存
+    pub fn handle_lyrics_offset_adjust(&mut self, delta_ms: i64) {
+        let track_path = match self.ensure_lyrics_target_loaded() {
+            Some(track_path) => track_path,
+            None => return,
+        };
+
+        self.lyrics_state.offset_manager.adjust_offset(&track_path, delta_ms);
+        if let Err(e) = self.lyrics_state.offset_manager.save_to_file() {
+            eprintln!("Failed to save lyrics offsets: {}", e);
+        }
+    }
+
+    pub fn handle_lyrics_offset_reset(&mut self) {
+        if let Some(track_path) = self.ensure_lyrics_target_loaded() {
+            self.lyrics_state.offset_manager.set_offset(&track_path, 0);
+            if let Err(e) = self.lyrics_state.offset_manager.save_to_file() {
+                eprintln!("Failed to save lyrics offsets: {}", e);
+            }
+        }
+    }
+
+    /// タイミング補正をLRCファイルの[offset:]タグに書き込み、楽曲ごとの補正をリセット
+    pub fn write_lyrics_offset_to_lrc(&mut self) {
+        self.ensure_lyrics_target_loaded();
+        let lrc_path = match self.lyrics_state.lyrics.as_ref().and_then(|lyrics| lyrics.source.as_ref()) {
+            Some(crate::lyrics::LyricsSource::LrcFile(lrc_path)) => lrc_path.clone(),
+            _ => return,
+        };
+        let user_offset_ms = self.lyrics_state.user_offset_ms();
+        if user_offset_ms == 0 {
+            return;
+        }
+
+        // [offset:]タグ以外の行はファイルの内容をそのまま残す
+        match crate::lyrics::LrcWriter::adjust_offset_in_file(&lrc_path, user_offset_ms) {
+            Ok(()) => {
+                self.handle_lyrics_offset_reset();
+                self.lyrics_state.reload();
+            }
+            Err(e) => eprintln!("Failed to write lyrics offset: {}", e),
         }
     }
 
@@ -125,7 +258,7 @@ impl MyApp {
         if position.as_secs() <= 3 {
             let shuffle_enabled = self.player_state.shuffle_enabled;
             if let Some(prev_track) = self.playlist_manager.move_to_previous_with_modes(shuffle_enabled) {
-                if let Err(_) = self.player_state.audio_player.play(prev_track) {
+                if let Err(_) = self.player_state.audio_player.play_with_fade(prev_track) {
                     // Handle error silently
                 } else if !was_playing {
                     // If it was paused before, pause the new track
@@ -197,7 +330,7 @@ impl MyApp {
         let shuffle_enabled = self.player_state.shuffle_enabled;
         
         if let Some(next_track) = self.playlist_manager.move_to_next_with_modes(repeat_mode, shuffle_enabled) {
-            if let Err(_) = self.player_state.audio_player.play(next_track) {
+            if let Err(_) = self.player_state.audio_player.play_with_fade(next_track) {
                 // Handle error silently
             } else if !was_playing {
                 // If it was paused before, pause the new track
@@ -472,4 +605,61 @@ impl MyApp {
             }
         }
     }
-}
\ No newline at end of file
+
+    /// 音量を変更して設定に保存
+    pub fn handle_set_volume(&mut self, volume: f32) {
+        self.player_state.audio_player.set_volume(volume);
+        // 音量を操作した場合はミュートを解除
+        self.player_state.audio_player.set_muted(false);
+        self.settings.set_volume(self.player_state.audio_player.get_volume());
+        self.settings.muted = false;
+        self.save_settings();
+    }
+
+    /// 現在の音量から増減（キーボードショートカット用）
+    pub fn handle_adjust_volume(&mut self, delta: f32) {
+        let volume = self.player_state.audio_player.get_volume() + delta;
+        self.handle_set_volume(volume);
+    }
+
+    /// 再生速度を変更（楽曲ごとに記憶する設定の場合は再生中の楽曲の速度として保存）
+    pub fn handle_set_playback_rate(&mut self, rate: f64) {
+        self.player_state.audio_player.set_playback_rate(rate);
+        if !self.settings.remember_playback_rate_per_track {
+            return;
+        }
+
+        let Some(track_path) = self.player_state.audio_player.get_current_track().map(|track| track.path.clone()) else {
+            return;
+        };
+        let rate = self.player_state.audio_player.get_playback_rate();
+        self.player_state.playback_rate_manager.set_rate(&track_path, rate);
+        if let Err(e) = self.player_state.playback_rate_manager.save_to_file() {
+            eprintln!("Warning: Failed to save playback rates: {}", e);
+        }
+        self.player_state.playback_rate_track = Some(track_path);
+    }
+
+    pub fn handle_set_preserve_pitch(&mut self, preserve_pitch: bool) {
+        self.player_state.audio_player.set_preserve_pitch(preserve_pitch);
+        self.settings.preserve_pitch = preserve_pitch;
+        self.save_settings();
+    }
+
+    /// 出力デバイスを切り替えて設定に保存（失敗した場合はエラーを表示して元のデバイスのまま）
+    pub fn handle_set_output_device(&mut self, output_device: Option<String>) {
+        match self.player_state.audio_player.set_output_device(output_device.clone()) {
+            Ok(()) => {
+                self.settings.output_device = output_device;
+                self.save_settings();
+            }
+            Err(e) => eprintln!("Warning: Failed to switch audio output device: {}", e),
+        }
+    }
+
+    pub fn handle_toggle_mute(&mut self) {
+        self.player_state.audio_player.toggle_mute();
+        self.settings.muted = self.player_state.audio_player.is_muted();
+        self.save_settings();
+    }
+}
@@ -10,15 +10,22 @@ use font_kit::properties::Properties;
 use font_kit::source::SystemSource;
 
 use crate::music::MusicLibrary;
+use crate::music::library_index::LibraryIndex;
+use crate::music::library_roots::ScanRoot;
+use crate::music::library_scanner::LibraryScanner;
+use crate::music::library_watcher::{LibraryWatcher, LIBRARY_WATCH_DEBOUNCE};
+use crate::music::replaygain_scanner::{self, ReplayGainScanner};
 use crate::player::PlaybackState;
 use crate::playlist::PlaylistManager;
 use crate::seek_points::SeekPoint;
 use crate::settings::Settings;
 
 #[allow(unused_imports)]
-pub use state::{UIState, SelectionState, PlayerState, PlaylistEditState, CoverArtCache, SeekPointEditState, Tab, RightTab};
+pub use state::{UIState, SelectionState, PlayerState, PlaylistEditState, CoverArtCache, CoverArtId, SeekPointEditState, LyricsState, Tab, RightTab};
 mod state;
 
+/// 次の楽曲を先読みして開始を予約する、現在の楽曲の残り時間（クロスフェード時間を除く）
+const GAPLESS_PRELOAD_TIME: std::time::Duration = std::time::Duration::from_secs(5);
 
 pub struct MyApp {
     pub ui_state: UIState,
@@ -27,9 +34,17 @@ pub struct MyApp {
     pub playlist_edit_state: PlaylistEditState,
     pub cover_art_cache: CoverArtCache,
     pub seek_point_edit_state: SeekPointEditState,
+    pub lyrics_state: LyricsState,
     pub settings: Settings,
     pub music_library: MusicLibrary,
     pub playlist_manager: PlaylistManager,
+    pub replay_gain_scanner: ReplayGainScanner,
+    pub library_scanner: LibraryScanner,
+    library_rescan_pending: bool, // スキャン中に再スキャンが要求された場合、中止後にやり直す
+    library_scan_started_at: Option<std::time::Instant>,
+    pub library_watcher: LibraryWatcher,
+    library_scan_roots: Vec<ScanRoot>, // 最後にスキャンを開始した時点の音楽フォルダと除外ルール
+    add_new_tracks_after_scan: bool, // 監視による再スキャンで見つかった楽曲をデフォルトプレイリストに追加する
 }
 
 impl MyApp {
@@ -38,11 +53,19 @@ impl MyApp {
         let mut app = Self {
             ui_state: UIState::new(&settings),
             selection_state: SelectionState::new(),
-            player_state: PlayerState::new(),
+            player_state: PlayerState::new(&settings),
             playlist_edit_state: PlaylistEditState::new(),
-            cover_art_cache: CoverArtCache::new(),
+            cover_art_cache: CoverArtCache::with_folder_image_names(settings.cover_art_file_names.clone()),
             seek_point_edit_state: SeekPointEditState::new(),
-            music_library: MusicLibrary::new(settings.classical_composer_hierarchy),
+            lyrics_state: LyricsState::new(),
+            music_library: {
+                let mut library_index = LibraryIndex::new();
+                // 起動時にライブラリのインデックスを読み込み
+                if let Err(e) = library_index.load_from_file() {
+                    eprintln!("Warning: Failed to load library index: {}", e);
+                }
+                MusicLibrary::with_index(settings.classical_composer_hierarchy, library_index)
+            },
             playlist_manager: {
                 let mut manager = PlaylistManager::auto_load().unwrap_or_else(|_| {
                     PlaylistManager::new_with_settings(
@@ -54,20 +77,74 @@ impl MyApp {
                 manager.apply_default_playlist_settings(&settings.default_playlist_settings);
                 
                 // 起動時にデフォルトプレイリストをクリア
-                if let Some(default_playlist) = manager.get_playlist_mut("default") {
-                    default_playlist.clear();
+                // （前回終了時に再生中だった場合は再生状態を復元するため残す）
+                let resuming_default = manager.get_current_playing_playlist_id() == Some("default")
+                    && manager.get_current_track().is_some();
+                if !resuming_default {
+                    if let Some(default_playlist) = manager.get_playlist_mut("default") {
+                        default_playlist.clear();
+                    }
                 }
                 
                 manager
             },
             settings,
+            replay_gain_scanner: ReplayGainScanner::new(),
+            library_scanner: LibraryScanner::new(),
+            library_rescan_pending: false,
+            library_scan_started_at: None,
+            library_watcher: LibraryWatcher::new(),
+            library_scan_roots: Vec::new(),
+            add_new_tracks_after_scan: false,
         };
+        app.apply_playback_settings();
+        app.restore_last_session();
         app.refresh_music_library();
         app
     }
 
+    /// 設定のうちオーディオプレイヤーに関するものを反映
+    pub fn apply_playback_settings(&mut self) {
+        self.player_state.audio_player.set_crossfade(
+            self.settings.get_crossfade_duration(),
+            self.settings.crossfade_curve,
+        );
+        self.player_state.audio_player.set_replay_gain(
+            self.settings.replay_gain_mode,
+            self.settings.replay_gain_preamp_db as f64,
+            self.settings.replay_gain_prevent_clipping,
+        );
+        self.player_state.audio_player.set_volume(self.settings.get_volume());
+        self.player_state.audio_player.set_muted(self.settings.muted);
+        self.player_state.audio_player.set_preserve_pitch(self.settings.preserve_pitch);
+    }
+
+    /// 前回終了時に再生中だった楽曲を、保存した再生位置で一時停止した状態に戻す
+    fn restore_last_session(&mut self) {
+        let Some(track) = self.playlist_manager.get_current_track().cloned() else {
+            return;
+        };
+        if !track.path.exists() {
+            self.playlist_manager.set_current_playing_index(None);
+            return;
+        }
+
+        let position = std::time::Duration::from_millis(self.settings.resume_position_ms);
+        if let Err(e) = self.player_state.audio_player.load_paused(track, position) {
+            eprintln!("Warning: Failed to restore last playback session: {}", e);
+            self.playlist_manager.set_current_playing_index(None);
+        }
+    }
+
     pub fn save_settings(&mut self) {
         self.settings.set_last_used_playlist(self.playlist_manager.get_current_active_playlist_id().to_string());
+        self.settings.repeat_mode = self.player_state.repeat_mode.clone();
+        self.settings.shuffle_enabled = self.player_state.shuffle_enabled;
+        self.settings.resume_position_ms = if self.player_state.audio_player.get_current_track().is_some() {
+            self.player_state.audio_player.get_playback_position().as_millis() as u64
+        } else {
+            0
+        };
         self.settings.update_playlist_display_order(self.playlist_manager.get_ordered_playlist_ids());
         
         // 分割比率を保存
@@ -79,17 +156,74 @@ impl MyApp {
         let _ = self.playlist_manager.auto_save();
     }
 
+    /// 音楽フォルダのスキャンをバックグラウンドで開始（完了までは現在のツリーを表示し続ける）
     pub fn refresh_music_library(&mut self) {
-        if !self.settings.target_directory.is_empty() {
-            let target_path = std::path::PathBuf::from(&self.settings.target_directory);
-            
-            eprintln!("Info: Scanning music directory: {}", target_path.display());
-            let start_time = std::time::Instant::now();
-            
-            self.music_library.scan_directory(&target_path);
+        self.add_new_tracks_after_scan = false;
+        if self.library_scanner.is_running() {
+            // 対象フォルダが変わった場合に備え、実行中のスキャンを中止してからやり直す
+            self.library_scanner.cancel();
+            self.library_rescan_pending = true;
+            return;
+        }
+
+        self.library_scan_roots = self.settings.library_roots.iter().map(ScanRoot::from_settings).collect();
+        let root_paths: Vec<std::path::PathBuf> = self.library_scan_roots.iter().map(|root| root.path.clone()).collect();
+        if let Err(e) = self.library_watcher.watch(&root_paths) {
+            eprintln!("Warning: {}", e);
+        }
+
+        if !self.library_scan_roots.is_empty() {
+            for root in &self.library_scan_roots {
+                eprintln!("Info: Scanning music directory: {}", root.path.display());
+            }
+            self.library_scan_started_at = Some(std::time::Instant::now());
+            self.library_scanner.start(self.library_scan_roots.clone(), self.music_library.take_index());
+        } else {
+            // 音楽フォルダが全て取り除かれた場合はライブラリを空にする
+            self.music_library.set_tracks(Vec::new());
             self.apply_search_filter();
-            
-            let duration = start_time.elapsed();
+        }
+    }
+
+    /// 音楽フォルダのスキャンの進捗を反映し、完了した場合はツリーを作り直す
+    fn poll_library_scan(&mut self, ctx: &egui::Context) {
+        if !self.library_scanner.is_running() {
+            return;
+        }
+
+        let Some(result) = self.library_scanner.poll() else {
+            // 進捗表示を更新し続ける
+            ctx.request_repaint_after(std::time::Duration::from_millis(100));
+            return;
+        };
+
+        if let Some(index) = result.index {
+            self.music_library.set_index(index);
+        }
+        if let Some(changes) = result.changes {
+            self.apply_library_changes(changes);
+            if std::mem::take(&mut self.library_rescan_pending) {
+                self.refresh_music_library();
+            }
+            ctx.request_repaint();
+            return;
+        }
+
+        let add_new_tracks = std::mem::take(&mut self.add_new_tracks_after_scan);
+        if let Some(tracks) = result.tracks {
+            let new_tracks = if add_new_tracks {
+                let known_paths: std::collections::HashSet<&std::path::Path> = self.music_library.get_tracks().iter()
+                    .map(|track| track.path.as_path())
+                    .collect();
+                tracks.iter().filter(|track| !known_paths.contains(track.path.as_path())).cloned().collect()
+            } else {
+                Vec::new()
+            };
+            self.music_library.set_tracks(tracks);
+            self.apply_search_filter();
+            self.add_new_tracks_to_default_playlist(new_tracks);
+
+            let duration = self.library_scan_started_at.map(|started_at| started_at.elapsed()).unwrap_or_default();
             let track_count = self.music_library.get_track_count();
             eprintln!("Info: Scanned {} tracks in {:.2}s", track_count, duration.as_secs_f64());
             
@@ -97,6 +231,101 @@ impl MyApp {
                 eprintln!("Info: Large library detected. Consider using search filters for better performance.");
             }
         }
+        self.library_scan_started_at = None;
+
+        if std::mem::take(&mut self.library_rescan_pending) {
+            self.refresh_music_library();
+        }
+        ctx.request_repaint();
+    }
+
+    /// 音楽フォルダ内の変更を、通知が落ち着いてからライブラリに反映
+    fn poll_library_watcher(&mut self, ctx: &egui::Context) {
+        if !self.library_watcher.is_watching() {
+            return;
+        }
+        // 通知を受け取るため、操作が無い間も定期的に確認する
+        ctx.request_repaint_after(LIBRARY_WATCH_DEBOUNCE);
+
+        // スキャン中の変更はスキャンの完了後に反映する
+        if self.library_scanner.is_running() {
+            return;
+        }
+        let Some(changes) = self.library_watcher.poll() else {
+            return;
+        };
+
+        if changes.rescan {
+            eprintln!("Info: Library watcher lost events, rescanning music directory");
+            self.refresh_music_library();
+            self.add_new_tracks_after_scan = true;
+            return;
+        }
+
+        for path in &changes.paths {
+            self.cover_art_cache.invalidate(path);
+        }
+        // タグの読み込みはスキャンスレッドで行い、完了時にツリーに反映する
+        self.library_scanner.start_changes(self.library_scan_roots.clone(), changes.paths, self.music_library.take_index());
+    }
+
+    /// 変更されたファイルを読み直した結果をツリーに反映
+    fn apply_library_changes(&mut self, changes: crate::music::library::ChangedTracks) {
+        let update = self.music_library.apply_changes(changes);
+        if update.is_empty() {
+            return;
+        }
+        eprintln!(
+            "Info: Library updated: {} added, {} updated, {} removed",
+            update.added.len(), update.updated, update.removed
+        );
+        self.apply_search_filter();
+        self.add_new_tracks_to_default_playlist(update.added);
+    }
+
+    /// 音楽フォルダに新しく追加された楽曲をデフォルトプレイリストの末尾に追加（設定で有効な場合）
+    fn add_new_tracks_to_default_playlist(&mut self, tracks: Vec<crate::music::TrackInfo>) {
+        if !self.settings.default_playlist_settings.auto_add_new_tracks || tracks.is_empty() {
+            return;
+        }
+
+        let mut added = false;
+        for track in tracks {
+            // 既にある楽曲は追加しない
+            added |= self.playlist_manager.add_track_to_playlist("default", track).is_ok();
+        }
+        if added {
+            let _ = self.playlist_manager.auto_save();
+        }
+    }
+
+    /// ReplayGainタグの無いFLACのラウドネス解析を開始
+    pub fn start_replay_gain_scan(&mut self) {
+        let albums = replaygain_scanner::albums_needing_scan(self.music_library.get_tracks());
+        self.replay_gain_scanner.start(albums, self.settings.replay_gain_write_tags);
+    }
+
+    /// 解析が完了した楽曲のゲインをライブラリとプレイリストに反映
+    fn poll_replay_gain_scan(&mut self, ctx: &egui::Context) {
+        if !self.replay_gain_scanner.is_running() {
+            return;
+        }
+
+        let gains = self.replay_gain_scanner.poll();
+        if !gains.is_empty() {
+            self.music_library.update_replay_gain(&gains);
+            self.playlist_manager.update_replay_gain(&gains);
+        }
+        if !self.replay_gain_scanner.is_running() {
+            // 解析結果を保存し、次回起動時に解析し直さないようにする
+            self.music_library.save_index();
+            if let Err(e) = self.playlist_manager.auto_save() {
+                eprintln!("Warning: Failed to save playlists: {}", e);
+            }
+            return;
+        }
+        // 進捗表示を更新し続ける
+        ctx.request_repaint_after(std::time::Duration::from_millis(200));
     }
 
     pub fn apply_search_filter(&mut self) {
@@ -154,6 +383,35 @@ impl MyApp {
         }
     }
 
+    /// 楽曲の同期歌詞（LRCファイルまたは埋め込み歌詞）の各行をシークポイントとして取り込む
+    pub fn import_seek_points_from_lyrics(&mut self, track_path: &std::path::Path) -> Result<usize, String> {
+        let lyrics = crate::lyrics::LyricsLoader::load_for_track(track_path)
+            .filter(|lyrics| lyrics.is_synced())
+            .ok_or_else(|| format!("No synced lyrics found for track: {}", track_path.display()))?;
+
+        let user_offset_ms = self.lyrics_state.offset_manager.get_offset(track_path);
+        let added = self.player_state.seek_point_manager.import_from_lyrics(track_path, &lyrics, user_offset_ms);
+        if added > 0 {
+            // 変更があった場合は保存
+            if let Err(e) = self.player_state.seek_point_manager.save_to_file() {
+                eprintln!("Warning: Failed to save seek points: {}", e);
+            }
+        }
+        Ok(added)
+    }
+
+    /// シークポイントを名前を歌詞テキストとしたLRCファイルに書き出す
+    pub fn export_seek_points_to_lrc(&self, track: &crate::music::TrackInfo, lrc_path: &std::path::Path) -> Result<(), String> {
+        let mut lyrics = self.player_state.seek_point_manager.export_as_lyrics(&track.path)
+            .ok_or_else(|| format!("No seek points found for track: {}", track.path.display()))?;
+        lyrics.metadata.title = Some(track.title.clone());
+        lyrics.metadata.artist = Some(track.artist.clone());
+        lyrics.metadata.album = Some(track.album.clone());
+
+        crate::lyrics::LrcWriter::save_to_file(lrc_path, &lyrics)
+            .map_err(|e| format!("Failed to write LRC file: {}", e))
+    }
+
     pub fn get_current_track_seek_points(&self) -> Option<&Vec<SeekPoint>> {
         if let Some(current_track) = self.playlist_manager.get_current_track() {
             self.player_state.seek_point_manager.get_seek_points(&current_track.path)
@@ -196,7 +454,12 @@ impl MyApp {
             let shuffle_enabled = self.player_state.shuffle_enabled;
             
             if let Some(next_track) = self.playlist_manager.move_to_next_with_modes(repeat_mode, shuffle_enabled) {
-                if let Err(_) = self.player_state.audio_player.play(next_track) {
+                let is_scheduled = self.player_state.audio_player.get_scheduled_track()
+                    .is_some_and(|scheduled| scheduled.path == next_track.path);
+                if is_scheduled {
+                    // 予約済みの楽曲は既に再生が始まっているので切り替えるだけ
+                    self.player_state.audio_player.advance_to_scheduled();
+                } else if let Err(_) = self.player_state.audio_player.play(next_track) {
                     // エラーの場合は停止状態にする
                     self.player_state.audio_player.stop();
                 }
@@ -205,7 +468,60 @@ impl MyApp {
                 self.player_state.audio_player.stop();
                 self.playlist_manager.set_current_playing_index(None);
             }
+        } else {
+            self.update_gapless_schedule();
+        }
+    }
+
+    /// 終了間際の楽曲について、次の楽曲の先読みと開始予約を行う（ギャップレス再生・クロスフェード）
+    fn update_gapless_schedule(&mut self) {
+        let audio_player = &self.player_state.audio_player;
+        if *audio_player.get_state() != PlaybackState::Playing {
+            return;
+        }
+        let preload_time = GAPLESS_PRELOAD_TIME + audio_player.get_crossfade_duration();
+        let near_end = audio_player.get_remaining_time()
+            .is_some_and(|remaining| remaining <= preload_time);
+        if !near_end {
+            return;
+        }
+
+        // 予約後にプレイリストやモードが変更された場合に備え、毎回次の楽曲を確認する
+        // （参照で比較し、予約し直す時だけ複製する）
+        let next_track = self.playlist_manager.peek_next_track_with_modes(
+            &self.player_state.repeat_mode,
+            self.player_state.shuffle_enabled,
+        );
+        let scheduled_path = audio_player.get_scheduled_track().map(|track| &track.path);
+        if next_track.map(|track| &track.path) == scheduled_path {
+            return;
+        }
+
+        let next_track = next_track.cloned();
+        let audio_player = &mut self.player_state.audio_player;
+        audio_player.cancel_scheduled_next();
+        if let Some(next_track) = next_track {
+            if let Err(error) = audio_player.schedule_next(next_track) {
+                eprintln!("Warning: Failed to preload next track: {}", error);
+            }
+        }
+    }
+
+    /// 再生中の楽曲が切り替わった時に、その楽曲に記憶した再生速度を適用する
+    fn apply_remembered_playback_rate(&mut self) {
+        if !self.settings.remember_playback_rate_per_track {
+            return;
+        }
+        let current_path = self.player_state.audio_player.get_current_track().map(|track| track.path.clone());
+        if current_path.is_none() || current_path == self.player_state.playback_rate_track {
+            return;
+        }
+
+        if let Some(track_path) = &current_path {
+            let rate = self.player_state.playback_rate_manager.get_rate(track_path);
+            self.player_state.audio_player.set_playback_rate(rate);
         }
+        self.player_state.playback_rate_track = current_path;
     }
 }
 
@@ -224,6 +540,10 @@ impl eframe::App for MyApp {
         
         self.handle_keyboard_shortcuts(ctx);
         self.check_playback_finished(); // 楽曲終了チェック
+        self.apply_remembered_playback_rate();
+        self.poll_replay_gain_scan(ctx);
+        self.poll_library_scan(ctx);
+        self.poll_library_watcher(ctx);
         self.render_menu_bar(ctx);
         self.render_tab_bar(ctx);
         self.render_central_panel(ctx);
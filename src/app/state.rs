@@ -1,10 +1,11 @@
 use eframe::egui;
 use std::collections::{HashMap, HashSet};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use crate::debug_ui::DebugUIRegions;
+use crate::lyrics::{Lyrics, LyricsLoader, LyricsOffsetManager, LyricsSyncEditor};
 use crate::music::TrackInfo;
-use crate::player::{AudioPlayer, PlaybackState};
+use crate::player::{AudioPlayer, PlaybackRateManager, PlaybackState};
 use crate::seek_points::SeekPointManager;
 use crate::settings::{Settings, RepeatMode};
 
@@ -32,6 +33,7 @@ pub struct UIState {
     pub right_top_bottom_position: f32,
     pub right_bottom_left_right_position: f32,
     pub debug_ui: DebugUIRegions,
+    pub output_devices: Option<Vec<String>>, // 設定画面に表示する出力デバイス一覧（未取得の場合はNone）
 }
 
 impl UIState {
@@ -44,6 +46,7 @@ impl UIState {
             right_top_bottom_position: settings.right_top_bottom_position,
             right_bottom_left_right_position: settings.right_bottom_left_right_position,
             debug_ui: DebugUIRegions::new(settings.debug_ui_regions),
+            output_devices: None,
         }
     }
 
@@ -84,22 +87,30 @@ pub struct PlayerState {
     pub repeat_mode: RepeatMode,
     pub shuffle_enabled: bool,
     pub seek_point_manager: SeekPointManager,
+    pub playback_rate_manager: PlaybackRateManager, // 楽曲ごとに記憶した再生速度
+    pub playback_rate_track: Option<PathBuf>,       // 記憶した再生速度を適用済みの楽曲
 }
 
 impl PlayerState {
-    pub fn new() -> Self {
+    pub fn new(settings: &Settings) -> Self {
         let mut seek_point_manager = SeekPointManager::new();
         // 起動時にシークポイントデータを読み込み
         if let Err(e) = seek_point_manager.load_from_file() {
             eprintln!("Warning: Failed to load seek points: {}", e);
         }
+        let mut playback_rate_manager = PlaybackRateManager::new();
+        if let Err(e) = playback_rate_manager.load_from_file() {
+            eprintln!("Warning: Failed to load playback rates: {}", e);
+        }
 
         Self {
-            audio_player: AudioPlayer::new(),
+            audio_player: AudioPlayer::new(settings.output_device.as_deref()),
             seek_drag_state: None,
-            repeat_mode: RepeatMode::Normal,
-            shuffle_enabled: false,
+            repeat_mode: settings.repeat_mode.clone(),
+            shuffle_enabled: settings.shuffle_enabled,
             seek_point_manager,
+            playback_rate_manager,
+            playback_rate_track: None,
         }
     }
 }
@@ -119,29 +130,162 @@ impl PlaylistEditState {
     }
 }
 
+/// デコードしたカバーアートのサムネイルを保持する数（超えた場合は最も長く使われていないものを破棄）
+const COVER_ART_CACHE_CAPACITY: usize = 64;
+/// サムネイルの最大辺（表示サイズの200pxより少し大きく）
+const COVER_ART_THUMBNAIL_SIZE: u32 = 256;
+
+/// 画像データのハッシュ（同じアルバムの楽曲など、同じ画像は同じ値になる）
+pub type CoverArtId = u64;
+
+struct CachedThumbnail {
+    texture: egui::TextureHandle,
+    last_used: u64,
+}
+
 /// カバーアートキャッシュ管理
+///
+/// 画像は表示する時にファイルから読み込み、同じ画像の楽曲で共有するサムネイルとして保持する。
+/// 埋め込み画像が無い楽曲は、同じフォルダのcover.jpgなどの画像を使う。
 pub struct CoverArtCache {
-    cache: HashMap<PathBuf, egui::TextureHandle>,
+    track_art: HashMap<PathBuf, Option<CoverArtId>>, // 楽曲ごとの画像（無い場合もNoneとして記録）
+    thumbnails: HashMap<CoverArtId, CachedThumbnail>,
+    undecodable: HashSet<CoverArtId>,                // デコードできなかった画像（毎フレーム読み直さない）
+    use_count: u64,
+    folder_image_names: Vec<String>,                 // フォルダ内で探す画像のファイル名（優先順）
+    folder_images: HashMap<PathBuf, Option<PathBuf>>, // フォルダごとに見つかった画像
 }
 
 impl CoverArtCache {
     pub fn new() -> Self {
         Self {
-            cache: HashMap::new(),
+            track_art: HashMap::new(),
+            thumbnails: HashMap::new(),
+            undecodable: HashSet::new(),
+            use_count: 0,
+            folder_image_names: crate::music::cover_art::default_folder_image_names(),
+            folder_images: HashMap::new(),
         }
     }
 
-    pub fn get(&self, path: &PathBuf) -> Option<&egui::TextureHandle> {
-        self.cache.get(path)
+    pub fn with_folder_image_names(folder_image_names: Vec<String>) -> Self {
+        Self {
+            folder_image_names,
+            ..Self::new()
+        }
+    }
+
+    /// フォルダ内で探す画像のファイル名を変更（既に調べた楽曲も次回表示時に探し直す）
+    pub fn set_folder_image_names(&mut self, folder_image_names: Vec<String>) {
+        self.folder_image_names = folder_image_names;
+        self.folder_images.clear();
+        self.track_art.clear();
     }
 
-    pub fn insert(&mut self, path: PathBuf, handle: egui::TextureHandle) {
-        self.cache.insert(path, handle);
+    /// 楽曲のカバーアートの識別子（画像の有無や、複数の楽曲で同じ画像かどうかの判定に使う）
+    pub fn art_id(&mut self, path: &Path) -> Option<CoverArtId> {
+        self.load(path).0
+    }
+
+    /// 複数の楽曲のカバーアートの識別子（複数選択時の比較用）
+    ///
+    /// UIスレッドを止めないよう、1回の呼び出しでファイルから読み込む楽曲は`max_loads`件までとし、
+    /// 読み込んでいない楽曲が残る間はNoneを返す（次のフレームで続きを読み込む）。
+    pub fn art_ids<'a>(&mut self, paths: impl IntoIterator<Item = &'a Path>, max_loads: usize) -> Option<Vec<Option<CoverArtId>>> {
+        let mut ids = Vec::new();
+        let mut loads = 0;
+        let mut complete = true;
+        for path in paths {
+            match self.track_art.get(path) {
+                Some(id) => ids.push(*id),
+                None if loads < max_loads => {
+                    loads += 1;
+                    ids.push(self.art_id(path));
+                }
+                None => complete = false,
+            }
+        }
+        complete.then_some(ids)
+    }
+
+    /// 楽曲のカバーアートのサムネイル（初回はファイルから読み込んでデコードする）
+    pub fn get_thumbnail(&mut self, ctx: &egui::Context, path: &Path) -> Option<egui::TextureHandle> {
+        let (id, data) = self.load(path);
+        let id = id.filter(|id| !self.undecodable.contains(id))?;
+        self.use_count += 1;
+        if let Some(cached) = self.thumbnails.get_mut(&id) {
+            cached.last_used = self.use_count;
+            return Some(cached.texture.clone());
+        }
+
+        let data = data.or_else(|| self.read_data(path))?;
+        let Ok(image) = image::load_from_memory(&data) else {
+            self.undecodable.insert(id);
+            return None;
+        };
+        let image = image.thumbnail(COVER_ART_THUMBNAIL_SIZE, COVER_ART_THUMBNAIL_SIZE).to_rgba8();
+        let size = [image.width() as usize, image.height() as usize];
+        let color_image = egui::ColorImage::from_rgba_unmultiplied(size, image.as_flat_samples().as_slice());
+        let texture = ctx.load_texture(format!("cover_art_{:016x}", id), color_image, egui::TextureOptions::default());
+
+        if self.thumbnails.len() >= COVER_ART_CACHE_CAPACITY {
+            let least_recently_used = self.thumbnails.iter()
+                .min_by_key(|(_, cached)| cached.last_used)
+                .map(|(id, _)| *id);
+            if let Some(least_recently_used) = least_recently_used {
+                self.thumbnails.remove(&least_recently_used);
+            }
+        }
+        self.thumbnails.insert(id, CachedThumbnail {
+            texture: texture.clone(),
+            last_used: self.use_count,
+        });
+        Some(texture)
+    }
+
+    /// ファイルが更新された楽曲の画像を次回表示時に読み直す
+    ///
+    /// フォルダ内の画像が追加・削除された場合に備え、同じフォルダの楽曲もまとめて読み直す。
+    pub fn invalidate(&mut self, path: &Path) {
+        let dir = if path.is_dir() { path } else { path.parent().unwrap_or(path) };
+        self.folder_images.retain(|folder, _| !folder.starts_with(dir));
+        self.track_art.retain(|track_path, _| !track_path.starts_with(dir));
+    }
+
+    #[allow(dead_code)]
+    pub fn thumbnail_count(&self) -> usize {
+        self.thumbnails.len()
+    }
+
+    /// 楽曲の画像の識別子と、まだ記録していなかった場合は読み込んだ画像データ
+    fn load(&mut self, path: &Path) -> (Option<CoverArtId>, Option<Vec<u8>>) {
+        if let Some(id) = self.track_art.get(path) {
+            return (*id, None);
+        }
+
+        let data = self.read_data(path);
+        let id = data.as_deref().map(|data| {
+            use std::hash::{Hash, Hasher};
+            let mut hasher = std::collections::hash_map::DefaultHasher::new();
+            data.hash(&mut hasher);
+            hasher.finish()
+        });
+        self.track_art.insert(path.to_path_buf(), id);
+        (id, data)
     }
 
+    /// 埋め込み画像、無い場合はフォルダ内の画像の画像データ
+    fn read_data(&mut self, path: &Path) -> Option<Vec<u8>> {
+        if let Some(data) = crate::music::cover_art::read_cover_art(path) {
+            return Some(data);
+        }
 
-    pub fn contains_key(&self, path: &PathBuf) -> bool {
-        self.cache.contains_key(path)
+        let dir = path.parent()?;
+        let names = &self.folder_image_names;
+        let image_path = self.folder_images.entry(dir.to_path_buf())
+            .or_insert_with(|| crate::music::cover_art::find_folder_image(dir, names))
+            .as_ref()?;
+        std::fs::read(image_path).ok()
     }
 }
 
@@ -149,6 +293,7 @@ impl CoverArtCache {
 pub struct SeekPointEditState {
     pub is_editing: bool,
     pub editing_names: HashMap<String, String>, // seek_point_id -> editing_text
+    pub status_message: Option<(PathBuf, String)>, // LRC取り込み・書き出しの結果表示（対象楽曲, メッセージ）
 }
 
 impl SeekPointEditState {
@@ -156,6 +301,7 @@ impl SeekPointEditState {
         Self {
             is_editing: false,
             editing_names: HashMap::new(),
+            status_message: None,
         }
     }
     
@@ -173,4 +319,78 @@ impl SeekPointEditState {
         self.is_editing = false;
         self.editing_names.clear();
     }
-}
\ No newline at end of file
+}
+
+/// 歌詞表示状態管理
+pub struct LyricsState {
+    pub track_path: Option<PathBuf>,           // 読み込み済み歌詞の楽曲パス
+    pub lyrics: Option<Lyrics>,                // 読み込み済み歌詞（見つからない場合はNone）
+    pub last_highlighted_index: Option<usize>, // 自動スクロール判定用
+    pub editor: Option<LyricsSyncEditor>,      // タイミング編集中の場合のみSome
+    pub edit_error: Option<String>,            // 保存失敗時のメッセージ
+    pub offset_manager: LyricsOffsetManager,   // 楽曲ごとのタイミング補正
+}
+
+impl LyricsState {
+    pub fn new() -> Self {
+        let mut offset_manager = LyricsOffsetManager::new();
+        // 起動時に歌詞オフセットデータを読み込み
+        if let Err(e) = offset_manager.load_from_file() {
+            eprintln!("Warning: Failed to load lyrics offsets: {}", e);
+        }
+
+        Self {
+            track_path: None,
+            lyrics: None,
+            last_highlighted_index: None,
+            editor: None,
+            edit_error: None,
+            offset_manager,
+        }
+    }
+
+    /// 表示中の楽曲のタイミング補正（ミリ秒、正の値で歌詞を早める）
+    pub fn user_offset_ms(&self) -> i64 {
+        self.track_path.as_deref()
+            .map(|track_path| self.offset_manager.get_offset(track_path))
+            .unwrap_or(0)
+    }
+
+    /// 楽曲が変わった場合のみ歌詞を読み込み直す（毎フレームのファイルアクセスを避ける）
+    pub fn ensure_loaded(&mut self, track_path: &Path) {
+        if self.track_path.as_deref() != Some(track_path) {
+            self.track_path = Some(track_path.to_path_buf());
+            self.lyrics = LyricsLoader::load_for_track(track_path);
+            self.last_highlighted_index = None;
+        }
+    }
+
+    pub fn reload(&mut self) {
+        if let Some(track_path) = self.track_path.take() {
+            self.ensure_loaded(&track_path);
+        }
+    }
+
+    /// 読み込み済みの歌詞を元にタイミング編集を開始
+    pub fn start_editing(&mut self, track_path: &Path) {
+        self.ensure_loaded(track_path);
+        self.editor = Some(LyricsSyncEditor::new(track_path, self.lyrics.as_ref()));
+        self.edit_error = None;
+        self.last_highlighted_index = None;
+    }
+
+    pub fn stop_editing(&mut self) {
+        self.editor = None;
+        self.edit_error = None;
+        self.last_highlighted_index = None;
+    }
+
+    pub fn is_editing(&self) -> bool {
+        self.editor.is_some()
+    }
+
+    /// 歌詞テキストの入力欄を表示中かどうか（キーボードショートカット無効化用）
+    pub fn is_text_input(&self) -> bool {
+        self.editor.as_ref().is_some_and(|editor| editor.is_text_input())
+    }
+}
@@ -27,10 +27,21 @@ impl DebugPlaybackControls {
         on_shuffle_change: &mut dyn FnMut(bool),
         on_add_seek_point: &mut dyn FnMut(),
         on_seek_to_point: &mut dyn FnMut(u64),
+        volume: f32,
+        muted: bool,
+        on_volume_change: &mut dyn FnMut(f32),
+        on_mute_toggle: &mut dyn FnMut(),
+        playback_rate: f64,
+        preserve_pitch: bool,
+        on_playback_rate_change: &mut dyn FnMut(f64),
+        on_preserve_pitch_change: &mut dyn FnMut(bool),
+        on_set_ab_loop_point: &mut dyn FnMut(crate::player::AbLoopPoint, Option<u64>),
+        on_clear_ab_loop: &mut dyn FnMut(),
     ) {
         // 必要なデータを取得
         let current_track = app.playlist_manager.get_current_track();
         let seek_points = app.get_current_track_seek_points();
+        let ab_loop = *app.player_state.audio_player.get_ab_loop();
 
         // PlaybackControls全体の利用可能領域を取得
         let total_available_rect = ui.available_rect_before_wrap();
@@ -46,7 +57,7 @@ impl DebugPlaybackControls {
         app.ui_state.debug_ui.draw_debug_rect_fixed(ui, seek_bar_rect, crate::debug_ui::ID_SEEK_BAR, "SeekBar");
 
         // シークバーの実際の描画
-        crate::ui::PlaybackControlsUI::show_seek_bar(ui, current_position, total_duration, seek_points, on_seek, on_seek_start, on_seek_end);
+        crate::ui::PlaybackControlsUI::show_seek_bar(ui, current_position, total_duration, seek_points, &ab_loop, on_seek, on_seek_start, on_seek_end);
 
         let space_height = 10.0;
         ui.add_space(space_height);
@@ -80,6 +91,10 @@ impl DebugPlaybackControls {
                 |ui| {
                     Self::show_playback_buttons(ui, playback_state, on_previous, on_seek_backward, on_play_pause, on_stop, on_seek_forward, on_next);
 
+                    ui.add_space(5.0);
+
+                    crate::ui::playback::PlaybackButtonsUI::show_playback_rate_control(ui, playback_rate, preserve_pitch, on_playback_rate_change, on_preserve_pitch_change);
+
                     ui.add_space(15.0);
 
                     // シークポイント追加ボタン
@@ -87,9 +102,23 @@ impl DebugPlaybackControls {
                         on_add_seek_point();
                     }
 
+                    ui.add_space(5.0);
+
+                    // A–Bリピート（現在位置から設定）
+                    crate::ui::playback::PlaybackButtonsUI::show_ab_loop_controls(
+                        ui,
+                        &ab_loop,
+                        &mut |point| on_set_ab_loop_point(point, None),
+                        on_clear_ab_loop,
+                    );
+
                     ui.add_space(15.0);
 
                     Self::show_repeat_shuffle_controls(ui, repeat_mode, shuffle_enabled, on_repeat_mode_change, on_shuffle_change);
+
+                    ui.add_space(10.0);
+
+                    crate::ui::playback::PlaybackButtonsUI::show_volume_control(ui, volume, muted, on_volume_change, on_mute_toggle);
                 }
             );
 
@@ -153,7 +182,12 @@ impl DebugPlaybackControls {
                             egui::Vec2::new(right_width, seek_points_list_height),
                             egui::Layout::top_down(egui::Align::LEFT),
                             |ui| {
-                                crate::ui::PlaybackControlsUI::show_current_track_seek_points(ui, seek_points, on_seek_to_point);
+                                crate::ui::PlaybackControlsUI::show_current_track_seek_points(
+                                    ui,
+                                    seek_points,
+                                    on_seek_to_point,
+                                    Some(&mut |point, position_ms| on_set_ab_loop_point(point, Some(position_ms))),
+                                );
                             }
                         );
                     } else {
@@ -188,7 +188,7 @@ impl RightPaneLayout {
                                     crate::app::ui::right_pane::SeekPointsUI::render(app, ui);
                                 },
                                 crate::app::state::RightTab::Lrc => {
-                                    ui.label("LRC歌詞表示機能は未実装です");
+                                    crate::app::ui::right_pane::LyricsUI::render(app, ui);
                                 },
                             }
                         });
@@ -0,0 +1,346 @@
+use eframe::egui;
+use crate::app::MyApp;
+use crate::lyrics::LyricsSource;
+use crate::player::PlaybackState;
+use crate::utils::formatting::TimeFormatter;
+
+pub struct LyricsUI;
+
+impl LyricsUI {
+    pub fn render(app: &mut MyApp, ui: &mut egui::Ui) {
+        // タイミング編集中は編集画面のみ表示
+        if app.lyrics_state.is_editing() {
+            Self::render_editor(app, ui);
+            return;
+        }
+
+        // 再生中の楽曲を優先し、なければ選択楽曲の歌詞を表示
+        let playing_track = app.playlist_manager.get_current_track().cloned();
+        let track = match app.lyrics_target_track() {
+            Some(track) => track,
+            None => {
+                ui.label("楽曲が選択されていません");
+                return;
+            }
+        };
+
+        app.lyrics_state.ensure_loaded(&track.path);
+
+        // ハイライトは再生中の楽曲を表示している場合のみ
+        let is_playing_track = playing_track.as_ref().is_some_and(|t| t.is_same_track(&track))
+            && *app.player_state.audio_player.get_state() != PlaybackState::Stopped;
+        let position_ms = app.player_state.audio_player.get_playback_position().as_millis() as u64;
+
+        let mut reload_clicked = false;
+        let mut edit_clicked = false;
+
+        ui.horizontal(|ui| {
+            ui.strong("♪");
+            ui.label(format!("{} - {}", track.artist, track.title));
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui.button("再読み込み").clicked() {
+                    reload_clicked = true;
+                }
+                if ui.button("同期編集").on_hover_text("再生しながらEnterで各行のタイミングを打刻").clicked() {
+                    edit_clicked = true;
+                }
+            });
+        });
+        ui.add_space(10.0);
+
+        if reload_clicked {
+            app.lyrics_state.reload();
+        }
+        if edit_clicked {
+            app.lyrics_state.start_editing(&track.path);
+            return;
+        }
+
+        let lyrics = match &app.lyrics_state.lyrics {
+            Some(lyrics) if !lyrics.is_empty() => lyrics,
+            _ => {
+                ui.label("歌詞が見つかりません");
+                ui.label("楽曲と同じフォルダに同じファイル名の .lrc ファイルを配置するか、");
+                ui.label("LYRICS / UNSYNCEDLYRICS タグに歌詞を埋め込むと表示されます");
+                app.lyrics_state.last_highlighted_index = None;
+                return;
+            }
+        };
+
+        // 楽曲ごとのタイミング補正（正の値で歌詞を早める）を再生位置側に反映
+        let user_offset_ms = app.lyrics_state.user_offset_ms();
+        let lyrics_position_ms = (position_ms as i64 + user_offset_ms).max(0) as u64;
+
+        let current_index = if is_playing_track && lyrics.is_synced() {
+            lyrics.find_current_line_index(lyrics_position_ms)
+        } else {
+            None
+        };
+        let should_scroll = current_index.is_some() && current_index != app.lyrics_state.last_highlighted_index;
+
+        // 読み込み元の表示
+        let source_text = match &lyrics.source {
+            Some(LyricsSource::LrcFile(_)) => "LRCファイル".to_string(),
+            Some(LyricsSource::Embedded(field_name)) => format!("埋め込み歌詞 ({})", field_name),
+            None => String::new(),
+        };
+        let sync_text = if lyrics.is_synced() { "同期" } else { "テキストのみ" };
+        ui.label(egui::RichText::new(format!("{} ・ {}", source_text, sync_text)).size(11.0).weak());
+
+        if let Some(author) = &lyrics.metadata.author {
+            ui.label(egui::RichText::new(format!("作成者: {}", author)).size(11.0).weak());
+        }
+
+        // 操作の収集用変数
+        let mut offset_delta_ms: Option<i64> = None;
+        let mut reset_offset_clicked = false;
+        let mut write_offset_clicked = false;
+
+        if lyrics.is_synced() {
+            ui.horizontal(|ui| {
+                ui.label(egui::RichText::new(format!("タイミング補正: {:+}ms", user_offset_ms)).size(11.0));
+                if ui.small_button("-0.1s").on_hover_text("歌詞を遅らせる (Ctrl+[)").clicked() {
+                    offset_delta_ms = Some(-100);
+                }
+                if ui.small_button("+0.1s").on_hover_text("歌詞を早める (Ctrl+])").clicked() {
+                    offset_delta_ms = Some(100);
+                }
+                if ui.add_enabled(user_offset_ms != 0, egui::Button::new("リセット").small()).clicked() {
+                    reset_offset_clicked = true;
+                }
+                let is_lrc_file = matches!(lyrics.source, Some(LyricsSource::LrcFile(_)));
+                if ui.add_enabled(is_lrc_file && user_offset_ms != 0, egui::Button::new("LRCに書き込み").small())
+                    .on_hover_text("補正値をLRCファイルの[offset:]タグに反映")
+                    .clicked()
+                {
+                    write_offset_clicked = true;
+                }
+            });
+        }
+        ui.add_space(5.0);
+
+        let highlight_color = egui::Color32::from_rgb(0, 150, 255);
+
+        let mut seek_to_ms: Option<u64> = None;
+
+        for (index, line) in lyrics.lines.iter().enumerate() {
+            // 空行（間奏等）は記号で表示して行の高さを確保
+            let text = if line.text.is_empty() { "♪" } else { line.text.as_str() };
+
+            let label = if Some(index) == current_index && line.has_word_timing() {
+                // 単語単位タイミングがある場合は歌い終えた部分のみ色を変える（カラオケ表示）
+                let sung_chars = lyrics.sung_char_count(index, lyrics_position_ms);
+                egui::Label::new(Self::karaoke_layout_job(ui, text, sung_chars, highlight_color))
+            } else if Some(index) == current_index {
+                egui::Label::new(egui::RichText::new(text).size(16.0).strong().color(highlight_color))
+            } else {
+                egui::Label::new(egui::RichText::new(text).size(14.0))
+            };
+
+            // 再生中の楽曲のタイムスタンプ付きの行はクリックでシーク
+            let line_time_ms = if is_playing_track {
+                lyrics.effective_time_ms(line).map(|time_ms| (time_ms as i64 - user_offset_ms).max(0) as u64)
+            } else {
+                None
+            };
+            let response = if let Some(time_ms) = line_time_ms {
+                let response = ui.add(label.sense(egui::Sense::click()))
+                    .on_hover_cursor(egui::CursorIcon::PointingHand)
+                    .on_hover_text(format!(
+                        "{} (クリックでシーク)",
+                        TimeFormatter::format_duration_with_millis(std::time::Duration::from_millis(time_ms))
+                    ));
+                if response.clicked() {
+                    seek_to_ms = Some(time_ms);
+                }
+                response
+            } else {
+                ui.add(label)
+            };
+
+            // 現在行が変わった時のみ自動スクロール（手動スクロールを妨げない）
+            if should_scroll && Some(index) == current_index {
+                response.scroll_to_me(Some(egui::Align::Center));
+            }
+
+            ui.add_space(4.0);
+        }
+
+        app.lyrics_state.last_highlighted_index = current_index;
+
+        // 操作の実行（借用チェッカー対応）
+        if let Some(time_ms) = seek_to_ms {
+            app.handle_seek_to_position(std::time::Duration::from_millis(time_ms));
+        }
+        if let Some(delta_ms) = offset_delta_ms {
+            app.handle_lyrics_offset_adjust(delta_ms);
+        }
+        if reset_offset_clicked {
+            app.handle_lyrics_offset_reset();
+        }
+        if write_offset_clicked {
+            app.write_lyrics_offset_to_lrc();
+        }
+    }
+
+    fn render_editor(app: &mut MyApp, ui: &mut egui::Ui) {
+        let is_target_playing = match (app.lyrics_state.editor.as_ref(), app.playlist_manager.get_current_track()) {
+            (Some(editor), Some(current)) => editor.track_path() == current.path.as_path(),
+            _ => false,
+        } && *app.player_state.audio_player.get_state() != PlaybackState::Stopped;
+        let edit_error = app.lyrics_state.edit_error.clone();
+        let last_scrolled_index = app.lyrics_state.last_highlighted_index;
+        let mut scrolled_index = last_scrolled_index;
+
+        let editor = match app.lyrics_state.editor.as_mut() {
+            Some(editor) => editor,
+            None => return,
+        };
+
+        // 操作の収集用変数
+        let mut action: Option<EditorAction> = None;
+
+        ui.horizontal(|ui| {
+            ui.strong("歌詞タイミング編集");
+            if let Some(file_name) = editor.track_path().file_name() {
+                ui.label(file_name.to_string_lossy());
+            }
+        });
+        ui.add_space(5.0);
+
+        if editor.is_text_input() {
+            ui.label("歌詞テキストを入力して「取り込み」を押してください（LRC形式も可）");
+            ui.add(egui::TextEdit::multiline(&mut editor.text_input)
+                .desired_rows(15)
+                .desired_width(f32::INFINITY));
+
+            ui.horizontal(|ui| {
+                if ui.button("取り込み").clicked() {
+                    action = Some(EditorAction::Import);
+                }
+                if ui.button("キャンセル").clicked() {
+                    action = Some(EditorAction::Cancel);
+                }
+            });
+        } else {
+            ui.horizontal(|ui| {
+                if ui.add_enabled(is_target_playing, egui::Button::new("打刻 (Enter)")).clicked() {
+                    action = Some(EditorAction::Tap);
+                }
+                if ui.add_enabled(editor.can_undo(), egui::Button::new("元に戻す (Ctrl+Z)")).clicked() {
+                    action = Some(EditorAction::Undo);
+                }
+                if ui.button("テキスト編集").clicked() {
+                    action = Some(EditorAction::BackToText);
+                }
+                ui.separator();
+                if ui.add_enabled(editor.can_save(), egui::Button::new("保存")).clicked() {
+                    action = Some(EditorAction::Save);
+                }
+                if ui.button("キャンセル").clicked() {
+                    action = Some(EditorAction::Cancel);
+                }
+            });
+
+            if !is_target_playing {
+                ui.label(egui::RichText::new("編集中の楽曲を再生すると打刻できます").size(11.0).weak());
+            }
+            let unstamped = editor.unstamped_count();
+            if unstamped > 0 {
+                ui.label(egui::RichText::new(format!("タイミング未設定の行: {} 行（全ての行を打刻すると保存できます）", unstamped)).size(11.0).weak());
+            }
+            if let Some(error) = &edit_error {
+                ui.colored_label(egui::Color32::RED, error);
+            }
+            ui.add_space(5.0);
+
+            let next_index = editor.next_index();
+            // 打刻位置が変わった時のみ自動スクロール
+            let should_scroll = Some(next_index) != last_scrolled_index;
+            scrolled_index = Some(next_index);
+            for (index, line) in editor.lines().iter().enumerate() {
+                ui.horizontal(|ui| {
+                    // 次に打刻する行の目印
+                    ui.label(if index == next_index { "▶" } else { "　" });
+
+                    let timestamp_text = match line.timestamp_ms {
+                        Some(timestamp_ms) => crate::lyrics::LrcWriter::format_timestamp(timestamp_ms),
+                        None => "--:--.--".to_string(),
+                    };
+                    ui.monospace(timestamp_text);
+
+                    let has_timestamp = line.timestamp_ms.is_some();
+                    if ui.add_enabled(has_timestamp, egui::Button::new("-0.1s").small()).clicked() {
+                        action = Some(EditorAction::Nudge(index, -100));
+                    }
+                    if ui.add_enabled(has_timestamp, egui::Button::new("+0.1s").small()).clicked() {
+                        action = Some(EditorAction::Nudge(index, 100));
+                    }
+                    if ui.add_enabled(has_timestamp, egui::Button::new("×").small())
+                        .on_hover_text("タイミングを削除")
+                        .clicked()
+                    {
+                        action = Some(EditorAction::Clear(index));
+                    }
+
+                    let text = if line.text.is_empty() { "♪" } else { line.text.as_str() };
+                    let response = ui.add(egui::Label::new(text).sense(egui::Sense::click()))
+                        .on_hover_text("クリックで次の打刻位置に設定");
+                    if response.clicked() {
+                        action = Some(EditorAction::SetNext(index));
+                    }
+                    if should_scroll && index == next_index {
+                        response.scroll_to_me(Some(egui::Align::Center));
+                    }
+                });
+            }
+        }
+
+        // 操作の実行（借用チェッカー対応）
+        app.lyrics_state.last_highlighted_index = scrolled_index;
+        let editor = match app.lyrics_state.editor.as_mut() {
+            Some(editor) => editor,
+            None => return,
+        };
+        match action {
+            Some(EditorAction::Import) => editor.import_text(),
+            Some(EditorAction::BackToText) => editor.back_to_text_input(),
+            Some(EditorAction::Undo) => { editor.undo(); }
+            Some(EditorAction::Nudge(index, delta_ms)) => { editor.nudge(index, delta_ms); }
+            Some(EditorAction::Clear(index)) => editor.clear_timestamp(index),
+            Some(EditorAction::SetNext(index)) => editor.set_next_index(index),
+            Some(EditorAction::Tap) => app.handle_lyrics_tap(),
+            Some(EditorAction::Save) => app.save_lyrics_edits(),
+            Some(EditorAction::Cancel) => app.lyrics_state.stop_editing(),
+            None => {}
+        }
+    }
+
+    fn karaoke_layout_job(ui: &egui::Ui, text: &str, sung_chars: usize, highlight_color: egui::Color32) -> egui::text::LayoutJob {
+        let font_id = egui::FontId::proportional(16.0);
+        let split_at = text.char_indices()
+            .nth(sung_chars)
+            .map(|(byte_index, _)| byte_index)
+            .unwrap_or(text.len());
+        let (sung, unsung) = text.split_at(split_at);
+
+        let mut job = egui::text::LayoutJob::default();
+        job.append(sung, 0.0, egui::TextFormat::simple(font_id.clone(), highlight_color));
+        job.append(unsung, 0.0, egui::TextFormat::simple(font_id, ui.visuals().strong_text_color()));
+        job
+    }
+}
+
+#[derive(Debug)]
+enum EditorAction {
+    Import,
+    BackToText,
+    Tap,
+    Undo,
+    Nudge(usize, i64),
+    Clear(usize),
+    SetNext(usize),
+    Save,
+    Cancel,
+}
@@ -2,8 +2,10 @@ pub mod layout;
 pub mod track_info;
 pub mod seek_points;
 pub mod playback_controls;
+pub mod lyrics;
 
 pub use layout::RightPaneLayout;
 pub use track_info::TrackInfoUI;
 pub use seek_points::SeekPointsUI;
-pub use playback_controls::PlaybackControlsOnlyUI;
\ No newline at end of file
+pub use playback_controls::PlaybackControlsOnlyUI;
+pub use lyrics::LyricsUI;
\ No newline at end of file
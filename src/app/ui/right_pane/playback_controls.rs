@@ -37,10 +37,25 @@ impl PlaybackControlsOnlyUI {
         let mut new_shuffle_enabled = app.player_state.shuffle_enabled;
         let mut add_seek_point_clicked = false;
         let mut seek_point_jump_position: Option<u64> = None;
+        let mut new_volume: Option<f32> = None;
+        let mut mute_toggled = false;
+        let mut new_playback_rate: Option<f64> = None;
+        let mut new_preserve_pitch: Option<bool> = None;
+        let mut ab_loop_point: Option<(crate::player::AbLoopPoint, Option<u64>)> = None;
+        let mut clear_ab_loop_clicked = false;
 
         // 必要なデータをコピー
         let repeat_mode = app.player_state.repeat_mode.clone();
         let shuffle_enabled = app.player_state.shuffle_enabled;
+        let volume = app.player_state.audio_player.get_volume();
+        let muted = app.player_state.audio_player.is_muted();
+        let playback_rate = app.player_state.audio_player.get_playback_rate();
+        let preserve_pitch = app.player_state.audio_player.is_preserve_pitch();
+
+        // 出力デバイスを開けない場合は再生できない理由を表示
+        if let Some(error) = app.player_state.audio_player.get_output_error() {
+            ui.label(egui::RichText::new(format!("⚠ {}", error)).color(egui::Color32::from_rgb(200, 80, 80)));
+        }
 
         // PlaybackControlsの内部領域をデバッグ描画対応バージョンで表示
         crate::app::ui::debug::DebugPlaybackControls::show_controls_with_seek_bar_debug(
@@ -71,6 +86,16 @@ impl PlaybackControlsOnlyUI {
             },
             &mut || add_seek_point_clicked = true,
             &mut |position_ms| seek_point_jump_position = Some(position_ms),
+            volume,
+            muted,
+            &mut |volume| new_volume = Some(volume),
+            &mut || mute_toggled = true,
+            playback_rate,
+            preserve_pitch,
+            &mut |rate| new_playback_rate = Some(rate),
+            &mut |enabled| new_preserve_pitch = Some(enabled),
+            &mut |point, position_ms| ab_loop_point = Some((point, position_ms)),
+            &mut || clear_ab_loop_clicked = true,
         );
 
         // Handle actions after UI (removed clear_queue handling)
@@ -116,6 +141,31 @@ impl PlaybackControlsOnlyUI {
             app.playlist_manager.update_shuffle_when_settings_changed(new_shuffle_enabled);
         }
 
+        // 音量・ミュートの変更処理
+        if let Some(volume) = new_volume {
+            app.handle_set_volume(volume);
+        }
+        if mute_toggled {
+            app.handle_toggle_mute();
+        }
+
+        // 再生速度の変更処理
+        if let Some(rate) = new_playback_rate {
+            app.handle_set_playback_rate(rate);
+        }
+        if let Some(preserve_pitch) = new_preserve_pitch {
+            app.handle_set_preserve_pitch(preserve_pitch);
+        }
+
+        // A–Bリピートの設定処理（位置の指定が無い場合は現在位置）
+        if let Some((point, position_ms)) = ab_loop_point {
+            let position = position_ms.map_or(current_position, std::time::Duration::from_millis);
+            app.player_state.audio_player.set_ab_loop_point(point, position);
+        }
+        if clear_ab_loop_clicked {
+            app.player_state.audio_player.clear_ab_loop();
+        }
+
         // シークポイントジャンプ処理
         if let Some(position_ms) = seek_point_jump_position {
             let jump_duration = std::time::Duration::from_millis(position_ms);
@@ -12,6 +12,8 @@ impl SeekPointsUI {
             let mut mode_changed = false;
             let mut should_start_editing = false;
             let mut should_stop_editing = false;
+            let mut import_clicked = false;
+            let mut export_clicked = false;
 
             // 現在の楽曲情報とモード切り替えボタンを表示
             ui.horizontal(|ui| {
@@ -34,9 +36,67 @@ impl SeekPointsUI {
                             should_start_editing = true;
                         }
                     }
+
+                    // LRC変換（編集中は名前の編集内容と競合するため無効）
+                    let can_convert = !app.seek_point_edit_state.is_editing;
+                    if ui.add_enabled(can_convert, egui::Button::new("LRC書き出し"))
+                        .on_hover_text("シークポイント名を歌詞としてLRCファイルに保存")
+                        .clicked()
+                    {
+                        export_clicked = true;
+                    }
+                    if ui.add_enabled(can_convert, egui::Button::new("LRC取り込み"))
+                        .on_hover_text("同期歌詞の各行をシークポイントとして追加")
+                        .clicked()
+                    {
+                        import_clicked = true;
+                    }
                 });
             });
 
+            // LRC取り込み・書き出し処理
+            let selected_track = selected_track.clone();
+            if import_clicked {
+                let message = match app.import_seek_points_from_lyrics(&selected_track.path) {
+                    Ok(0) => "追加できる行がありませんでした".to_string(),
+                    Ok(added) => format!("{}件のシークポイントを取り込みました", added),
+                    Err(error) => {
+                        eprintln!("Error importing seek points: {}", error);
+                        "同期歌詞が見つかりません".to_string()
+                    }
+                };
+                app.seek_point_edit_state.status_message = Some((selected_track.path.clone(), message));
+            }
+            if export_clicked {
+                let mut dialog = rfd::FileDialog::new().add_filter("LRC", &["lrc"]);
+                if let Some(directory) = selected_track.path.parent() {
+                    dialog = dialog.set_directory(directory);
+                }
+                if let Some(file_name) = crate::lyrics::LyricsLoader::default_lrc_path(&selected_track.path)
+                    .and_then(|path| path.file_name().map(|name| name.to_string_lossy().to_string()))
+                {
+                    dialog = dialog.set_file_name(file_name);
+                }
+
+                if let Some(lrc_path) = dialog.save_file() {
+                    let message = match app.export_seek_points_to_lrc(&selected_track, &lrc_path) {
+                        Ok(()) => format!("{} に書き出しました", lrc_path.display()),
+                        Err(error) => {
+                            eprintln!("Error exporting seek points: {}", error);
+                            "書き出しに失敗しました".to_string()
+                        }
+                    };
+                    app.seek_point_edit_state.status_message = Some((selected_track.path.clone(), message));
+                    // 表示中の歌詞ファイルを上書きした可能性があるため読み込み直す
+                    app.lyrics_state.reload();
+                }
+            }
+            if let Some((track_path, message)) = &app.seek_point_edit_state.status_message {
+                if *track_path == selected_track.path {
+                    ui.label(egui::RichText::new(message).size(11.0).weak());
+                }
+            }
+
             // モード変更処理
             if mode_changed {
                 if should_stop_editing {
@@ -1,6 +1,9 @@
 use eframe::egui;
 use crate::app::MyApp;
 
+/// 複数選択時に1フレームでカバーアートを読み込む楽曲数
+const COVER_ART_LOADS_PER_FRAME: usize = 8;
+
 pub struct TrackInfoUI;
 
 impl TrackInfoUI {
@@ -30,15 +33,16 @@ impl TrackInfoUI {
             };
 
             if !selected_tracks.is_empty() {
-                Self::show_multiple_tracks_details_static(ui, &selected_tracks);
+                Self::show_multiple_tracks_details_static(ui, &mut app.cover_art_cache, &selected_tracks);
             }
         }
     }
 
     fn show_track_details(app: &mut MyApp, ui: &mut egui::Ui, track: &crate::music::TrackInfo) {
         // カバーアートがある場合は先に表示
-        if let Some(cover_art_data) = &track.cover_art {
-            Self::show_cover_art(app, ui, track, cover_art_data);
+        let cover_art = app.cover_art_cache.art_id(&track.path);
+        if cover_art.is_some() {
+            Self::show_cover_art(app, ui, track);
             ui.add_space(10.0);
         }
 
@@ -95,7 +99,7 @@ impl TrackInfoUI {
                         ui.end_row();
 
                         ui.strong("カバーアート:");
-                        if track.cover_art.is_some() {
+                        if cover_art.is_some() {
                             ui.label("あり");
                         } else {
                             ui.label("なし");
@@ -132,13 +136,29 @@ impl TrackInfoUI {
                     });
     }
 
-    fn show_multiple_tracks_details_static(ui: &mut egui::Ui, tracks: &[crate::music::TrackInfo]) {
+    fn show_multiple_tracks_details_static(ui: &mut egui::Ui, cover_art_cache: &mut crate::app::CoverArtCache, tracks: &[crate::music::TrackInfo]) {
         if tracks.is_empty() {
             return;
         }
 
-        // カバーアートの処理
-        Self::show_multiple_cover_arts(ui, tracks);
+        // カバーアートの処理（多数の楽曲を選択した場合は数フレームに分けて読み込む）
+        let paths = tracks.iter().map(|track| track.path.as_path());
+        let cover_arts = cover_art_cache.art_ids(paths, COVER_ART_LOADS_PER_FRAME);
+        match &cover_arts {
+            Some(cover_arts) => Self::show_multiple_cover_arts(ui, cover_art_cache, tracks, cover_arts),
+            None => {
+                ui.with_layout(egui::Layout::top_down(egui::Align::Center), |ui| {
+                    ui.allocate_ui_with_layout(
+                        egui::Vec2::new(200.0, 200.0),
+                        egui::Layout::centered_and_justified(egui::Direction::TopDown),
+                        |ui| {
+                            ui.label("カバーアートを確認中...");
+                        }
+                    );
+                });
+                ui.ctx().request_repaint();
+            }
+        }
         ui.add_space(10.0);
 
         // 共通の値を持つかどうかを判定するヘルパー関数
@@ -233,15 +253,19 @@ impl TrackInfoUI {
 
                 // カバーアート
                 ui.strong("カバーアート:");
-                let first_has_cover = tracks[0].cover_art.is_some();
-                if tracks.iter().all(|track| track.cover_art.is_some() == first_has_cover) {
-                    if first_has_cover {
-                        ui.label("あり");
+                if let Some(cover_arts) = &cover_arts {
+                    let first_has_cover = cover_arts[0].is_some();
+                    if cover_arts.iter().all(|cover_art| cover_art.is_some() == first_has_cover) {
+                        if first_has_cover {
+                            ui.label("あり");
+                        } else {
+                            ui.label("なし");
+                        }
                     } else {
-                        ui.label("なし");
+                        ui.label("複数の値があります");
                     }
                 } else {
-                    ui.label("複数の値があります");
+                    ui.label("確認中...");
                 }
                 ui.end_row();
 
@@ -263,22 +287,9 @@ impl TrackInfoUI {
             });
     }
 
-    fn show_cover_art(app: &mut MyApp, ui: &mut egui::Ui, track: &crate::music::TrackInfo, cover_art_data: &[u8]) {
-        // キャッシュから既存のテクスチャを確認
-        if !app.cover_art_cache.contains_key(&track.path) {
-            // 画像をデコードしてテクスチャを作成
-            if let Ok(image) = image::load_from_memory(cover_art_data) {
-                let rgba_image = image.to_rgba8();
-                let size = [rgba_image.width() as usize, rgba_image.height() as usize];
-                let pixels = rgba_image.as_flat_samples();
-                let color_image = egui::ColorImage::from_rgba_unmultiplied(size, pixels.as_slice());
-                let texture = ui.ctx().load_texture("cover_art", color_image, egui::TextureOptions::default());
-                app.cover_art_cache.insert(track.path.clone(), texture);
-            }
-        }
-
-        // テクスチャがあれば表示
-        if let Some(texture) = app.cover_art_cache.get(&track.path) {
+    fn show_cover_art(app: &mut MyApp, ui: &mut egui::Ui, track: &crate::music::TrackInfo) {
+        // サムネイルがあれば表示（初回はデコードしてキャッシュ）
+        if let Some(texture) = app.cover_art_cache.get_thumbnail(ui.ctx(), &track.path) {
             let max_size = 200.0; // 最大サイズを200pxに制限
             let image_size = texture.size_vec2();
             let scale = (max_size / image_size.x.max(image_size.y)).min(1.0);
@@ -286,39 +297,32 @@ impl TrackInfoUI {
 
             // 画像を中央揃えで表示
             ui.with_layout(egui::Layout::top_down(egui::Align::Center), |ui| {
-                ui.add(egui::Image::from_texture(texture).max_size(scaled_size));
+                ui.add(egui::Image::from_texture(&texture).max_size(scaled_size));
             });
         }
     }
 
-    fn show_multiple_cover_arts(ui: &mut egui::Ui, tracks: &[crate::music::TrackInfo]) {
-        if tracks.is_empty() {
+    fn show_multiple_cover_arts(
+        ui: &mut egui::Ui,
+        cover_art_cache: &mut crate::app::CoverArtCache,
+        tracks: &[crate::music::TrackInfo],
+        cover_arts: &[Option<crate::app::CoverArtId>],
+    ) {
+        if cover_arts.is_empty() {
             return;
         }
 
-        // カバーアートの比較
-        let first_cover_art = &tracks[0].cover_art;
-        let all_same_cover = tracks.iter().all(|track| {
-            match (&track.cover_art, first_cover_art) {
-                (Some(data1), Some(data2)) => data1 == data2,
-                (None, None) => true,
-                _ => false,
-            }
-        });
+        // カバーアートの比較（画像データのハッシュで比較）
+        let first_cover_art = &cover_arts[0];
+        let all_same_cover = cover_arts.iter().all(|cover_art| cover_art == first_cover_art);
 
         let max_size = 200.0; // 単一選択時と同じサイズ
 
         ui.with_layout(egui::Layout::top_down(egui::Align::Center), |ui| {
             if all_same_cover {
-                if let Some(cover_art_data) = first_cover_art {
+                if first_cover_art.is_some() {
                     // 同じカバーアートの場合は実際の画像を表示
-                    if let Ok(image) = image::load_from_memory(cover_art_data) {
-                        let rgba_image = image.to_rgba8();
-                        let size = [rgba_image.width() as usize, rgba_image.height() as usize];
-                        let pixels = rgba_image.as_flat_samples();
-                        let color_image = egui::ColorImage::from_rgba_unmultiplied(size, pixels.as_slice());
-                        let texture = ui.ctx().load_texture("multiple_cover_art", color_image, egui::TextureOptions::default());
-
+                    if let Some(texture) = cover_art_cache.get_thumbnail(ui.ctx(), &tracks[0].path) {
                         let image_size = texture.size_vec2();
                         let scale = (max_size / image_size.x.max(image_size.y)).min(1.0);
                         let scaled_size = image_size * scale;
@@ -163,7 +163,7 @@ impl MyApp {
     }
 
     pub fn render_left_pane(&mut self, ui: &mut egui::Ui) {
-        if self.settings.target_directory.is_empty() {
+        if self.settings.library_roots.is_empty() {
             ui.vertical_centered(|ui| {
                 ui.add_space(50.0);
                 ui.label("対象ディレクトリが設定されていません。");
@@ -175,7 +175,13 @@ impl MyApp {
                 .auto_shrink([false, false])
                 .scroll_bar_visibility(egui::scroll_area::ScrollBarVisibility::VisibleWhenNeeded)
                 .show(ui, |ui| {
-                    ui.label(format!("対象ディレクトリ: {}", self.settings.target_directory));
+                    let root_paths: Vec<&str> = self.settings.library_roots.iter().map(|root| root.path.as_str()).collect();
+                    ui.label(format!("対象ディレクトリ: {}", root_paths.join(", ")));
+                    if self.library_scanner.is_running() && !self.library_scanner.get_progress().changes_only {
+                        self.render_library_scan_progress(ui);
+                    } else {
+                        self.render_unplayable_files_notice(ui);
+                    }
                     ui.separator();
                     
                     // Search UI
@@ -200,6 +206,59 @@ impl MyApp {
         }
     }
 
+    /// 音楽フォルダのスキャンの進捗（スキャン中も現在のツリーは操作できる）
+    fn render_library_scan_progress(&mut self, ui: &mut egui::Ui) {
+        let progress = self.library_scanner.get_progress();
+        let (fraction, text) = match progress.total {
+            Some(total) if total > 0 => (
+                progress.completed as f32 / total as f32,
+                format!("{} / {} 曲", progress.completed, total),
+            ),
+            Some(_) => (1.0, "0 / 0 曲".to_string()),
+            None => (0.0, format!("ファイルを検索中… {}件", progress.files_found)),
+        };
+        let current_directory = progress.current_directory.as_ref().map(|path| path.display().to_string());
+
+        let mut cancel_clicked = false;
+        ui.horizontal(|ui| {
+            ui.add(egui::ProgressBar::new(fraction).text(text).desired_width(240.0));
+            if progress.cancelled {
+                ui.label(egui::RichText::new("中止しています…").size(11.0).weak());
+            } else if ui.button("中止").clicked() {
+                cancel_clicked = true;
+            }
+        });
+        if let Some(current_directory) = current_directory {
+            ui.label(egui::RichText::new(current_directory).size(11.0).weak());
+        }
+
+        if cancel_clicked {
+            self.library_scanner.cancel();
+        }
+    }
+
+    /// 前回のスキャンで再生できない形式のため取り込まなかったファイルの件数（一覧はツールチップ）
+    fn render_unplayable_files_notice(&self, ui: &mut egui::Ui) {
+        let unplayable_files = &self.library_scanner.get_progress().unplayable_files;
+        if unplayable_files.is_empty() {
+            return;
+        }
+
+        const MAX_LISTED_FILES: usize = 20;
+        let mut file_list: Vec<String> = unplayable_files.iter()
+            .take(MAX_LISTED_FILES)
+            .map(|path| path.display().to_string())
+            .collect();
+        if unplayable_files.len() > MAX_LISTED_FILES {
+            file_list.push(format!("…他{}件", unplayable_files.len() - MAX_LISTED_FILES));
+        }
+        ui.label(
+            egui::RichText::new(format!("⚠ 再生できない形式（Opus）のファイル{}件を取り込んでいません", unplayable_files.len()))
+                .size(11.0)
+                .color(egui::Color32::from_rgb(200, 140, 0))
+        ).on_hover_text(file_list.join("\n"));
+    }
+
     pub fn render_music_tree(&mut self, ui: &mut egui::Ui) {
         let mut track_selection = None;
         let mut double_clicked_track = None;
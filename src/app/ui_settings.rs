@@ -5,31 +5,25 @@ impl MyApp {
     pub fn render_settings_tab(&mut self, ui: &mut egui::Ui) {
         ui.add_space(20.0);
         
+        self.render_library_roots_settings(ui);
+        
+        ui.add_space(20.0);
+        
         ui.horizontal(|ui| {
-            ui.label("対象ディレクトリ:");
-            ui.add_space(10.0);
-            
-            let response = ui.text_edit_singleline(&mut self.settings.target_directory);
+            let response = ui.checkbox(&mut self.settings.classical_composer_hierarchy, 
+                "クラシック音楽（ジャンルが\"Classical\"）では作曲家階層を追加");
             if response.changed() {
+                self.music_library.set_classical_hierarchy(self.settings.classical_composer_hierarchy);
                 self.save_settings();
             }
-            
-            if ui.button("選択").clicked() {
-                if let Some(path) = rfd::FileDialog::new().pick_folder() {
-                    self.settings.target_directory = path.display().to_string();
-                    self.save_settings();
-                    self.refresh_music_library();
-                }
-            }
         });
         
         ui.add_space(20.0);
         
         ui.horizontal(|ui| {
-            let response = ui.checkbox(&mut self.settings.classical_composer_hierarchy, 
-                "クラシック音楽（ジャンルが\"Classical\"）では作曲家階層を追加");
+            let response = ui.checkbox(&mut self.settings.default_playlist_settings.auto_add_new_tracks,
+                "対象ディレクトリに追加された楽曲をデフォルトプレイリストに自動で追加");
             if response.changed() {
-                self.music_library.set_classical_hierarchy(self.settings.classical_composer_hierarchy);
                 self.save_settings();
             }
         });
@@ -94,12 +88,74 @@ impl MyApp {
                 ui.ctx().request_repaint();
             }
         });
-        
+
+        ui.add_space(20.0);
+
+        // カバーアート設定
+        ui.horizontal(|ui| {
+            ui.label("カバーアート画像のファイル名:");
+            ui.add_space(10.0);
+
+            let mut file_names = self.settings.cover_art_file_names.join(",");
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut file_names)
+                    .hint_text("cover,folder,front,albumart*")
+                    .desired_width(300.0)
+            );
+            if response.changed() {
+                self.settings.cover_art_file_names = file_names.split(',').map(str::to_string).collect();
+                self.cover_art_cache.set_folder_image_names(self.settings.cover_art_file_names.clone());
+                self.save_settings();
+            }
+        });
+        ui.label("埋め込み画像が無い楽曲は、同じフォルダにあるこれらの名前の画像を表示します（カンマ区切り、先頭ほど優先、拡張子不要）");
+
         ui.add_space(20.0);
         ui.separator();
         ui.heading("プレイバック設定");
         ui.add_space(10.0);
         
+        // 出力デバイス設定（一覧は設定画面を初めて開いた時と再読み込み時に取得）
+        let output_devices = self.ui_state.output_devices
+            .get_or_insert_with(crate::player::output_device::list_output_devices)
+            .clone();
+        let mut selected_output_device: Option<Option<String>> = None;
+        let mut refresh_output_devices = false;
+        ui.horizontal(|ui| {
+            ui.label("出力デバイス:");
+            ui.add_space(10.0);
+            
+            let current_device = self.player_state.audio_player.get_output_device().map(str::to_string);
+            egui::ComboBox::from_id_source("output_device_selector")
+                .selected_text(current_device.as_deref().unwrap_or("既定のデバイス"))
+                .width(260.0)
+                .show_ui(ui, |ui| {
+                    if ui.selectable_label(current_device.is_none(), "既定のデバイス").clicked() {
+                        selected_output_device = Some(None);
+                    }
+                    for device in &output_devices {
+                        if ui.selectable_label(current_device.as_ref() == Some(device), device).clicked() {
+                            selected_output_device = Some(Some(device.clone()));
+                        }
+                    }
+                });
+            
+            if ui.button("🔄").on_hover_text("デバイス一覧を再読み込み").clicked() {
+                refresh_output_devices = true;
+            }
+        });
+        if let Some(error) = self.player_state.audio_player.get_output_error() {
+            ui.label(egui::RichText::new(error).size(11.0).color(egui::Color32::from_rgb(200, 80, 80)));
+        }
+        if let Some(output_device) = selected_output_device {
+            self.handle_set_output_device(output_device);
+        }
+        if refresh_output_devices {
+            self.ui_state.output_devices = None;
+        }
+        
+        ui.add_space(10.0);
+        
         // シーク秒数設定
         ui.horizontal(|ui| {
             ui.label("シーク秒数:");
@@ -119,6 +175,160 @@ impl MyApp {
             ui.label("(↩/↪ ボタンで前後にジャンプする秒数)");
         });
         
+        ui.add_space(10.0);
+        
+        // クロスフェード設定
+        ui.horizontal(|ui| {
+            ui.label("クロスフェード:");
+            ui.add_space(10.0);
+            
+            let mut crossfade_changed = false;
+            let mut crossfade_seconds = self.settings.crossfade_seconds;
+            if ui.add(egui::DragValue::new(&mut crossfade_seconds)
+                .range(0.0..=10.0)
+                .speed(0.1)
+                .fixed_decimals(1)
+                .suffix("秒")).changed()
+            {
+                self.settings.set_crossfade_seconds(crossfade_seconds);
+                crossfade_changed = true;
+            }
+            
+            ui.add_space(10.0);
+            
+            egui::ComboBox::from_id_source("crossfade_curve_selector")
+                .selected_text(self.settings.crossfade_curve.display_name())
+                .show_ui(ui, |ui| {
+                    for curve in [crate::settings::CrossfadeCurve::Linear, crate::settings::CrossfadeCurve::SCurve] {
+                        if ui.selectable_value(&mut self.settings.crossfade_curve, curve, curve.display_name()).changed() {
+                            crossfade_changed = true;
+                        }
+                    }
+                });
+            
+            if crossfade_changed {
+                self.apply_playback_settings();
+                self.save_settings();
+            }
+            
+            ui.add_space(10.0);
+            ui.label("(0秒の場合は曲間なしで切り替え)");
+        });
+        
+        ui.add_space(10.0);
+        
+        // 再生速度設定
+        ui.horizontal(|ui| {
+            ui.label("再生速度:");
+            ui.add_space(10.0);
+            
+            if ui.checkbox(&mut self.settings.remember_playback_rate_per_track, "楽曲ごとに記憶").changed() {
+                // 再生中の楽曲の速度はそのままにし、次の楽曲から記憶した速度を適用する
+                self.player_state.playback_rate_track = self.player_state.audio_player.get_current_track()
+                    .map(|track| track.path.clone());
+                self.save_settings();
+            }
+            
+            ui.add_space(10.0);
+            ui.label("(オフの場合は次の楽曲にも同じ速度を使用し、終了時に等速に戻る)");
+        });
+        
+        ui.add_space(10.0);
+        
+        // ReplayGain設定
+        ui.horizontal(|ui| {
+            ui.label("ReplayGain:");
+            ui.add_space(10.0);
+            
+            let mut replay_gain_changed = false;
+            egui::ComboBox::from_id_source("replay_gain_mode_selector")
+                .selected_text(self.settings.replay_gain_mode.display_name())
+                .show_ui(ui, |ui| {
+                    for mode in [
+                        crate::settings::ReplayGainMode::Off,
+                        crate::settings::ReplayGainMode::Track,
+                        crate::settings::ReplayGainMode::Album,
+                    ] {
+                        if ui.selectable_value(&mut self.settings.replay_gain_mode, mode, mode.display_name()).changed() {
+                            replay_gain_changed = true;
+                        }
+                    }
+                });
+            
+            ui.add_space(10.0);
+            ui.label("プリアンプ:");
+            let mut preamp_db = self.settings.replay_gain_preamp_db;
+            if ui.add(egui::DragValue::new(&mut preamp_db)
+                .range(-15.0..=15.0)
+                .speed(0.1)
+                .fixed_decimals(1)
+                .suffix("dB")).changed()
+            {
+                self.settings.set_replay_gain_preamp_db(preamp_db);
+                replay_gain_changed = true;
+            }
+            
+            ui.add_space(10.0);
+            if ui.checkbox(&mut self.settings.replay_gain_prevent_clipping, "クリッピング防止").changed() {
+                replay_gain_changed = true;
+            }
+            
+            if replay_gain_changed {
+                self.apply_playback_settings();
+                self.save_settings();
+            }
+        });
+        
+        // ラウドネス解析（ReplayGainタグの無いFLACが対象）
+        ui.horizontal(|ui| {
+            let is_running = self.replay_gain_scanner.is_running();
+            if ui.add_enabled(!is_running, egui::Button::new("ラウドネス解析"))
+                .on_hover_text("ReplayGainタグの無いFLACをEBU R128で解析（アルバム単位）")
+                .clicked()
+            {
+                self.start_replay_gain_scan();
+            }
+            if is_running && ui.button("中止").clicked() {
+                self.replay_gain_scanner.cancel();
+            }
+            
+            ui.add_space(10.0);
+            if ui.add_enabled(!is_running, egui::Checkbox::new(
+                &mut self.settings.replay_gain_write_tags,
+                "解析結果をタグに書き込む",
+            )).changed() {
+                self.save_settings();
+            }
+        });
+        
+        let progress = self.replay_gain_scanner.get_progress();
+        if self.replay_gain_scanner.is_running() {
+            let fraction = if progress.total > 0 {
+                progress.completed as f32 / progress.total as f32
+            } else {
+                0.0
+            };
+            ui.add(egui::ProgressBar::new(fraction)
+                .text(format!("{} / {} 曲", progress.completed, progress.total)));
+            if let Some(current) = progress.current.as_ref().and_then(|path| path.file_name()) {
+                ui.label(egui::RichText::new(current.to_string_lossy()).size(11.0).weak());
+            }
+        } else if progress.finished {
+            let summary = if progress.cancelled {
+                format!("中止しました（{}曲を解析済み）", progress.analyzed)
+            } else if progress.total == 0 {
+                "解析が必要な楽曲はありません".to_string()
+            } else {
+                format!("{}曲を解析しました", progress.analyzed)
+            };
+            ui.label(egui::RichText::new(summary).size(11.0).weak());
+            if !progress.failures.is_empty() {
+                ui.label(egui::RichText::new(format!("{}件のエラー（詳細はログを参照）", progress.failures.len()))
+                    .size(11.0)
+                    .color(egui::Color32::from_rgb(200, 80, 80)));
+            }
+        }
+        
         // デバッグ設定
         ui.add_space(20.0);
         ui.separator();
@@ -163,4 +373,79 @@ impl MyApp {
             self.playlist_manager.optimize_memory();
         }
     }
+
+    /// 音楽フォルダの一覧（フォルダごとに除外パターンとシンボリックリンクの扱いを設定）
+    fn render_library_roots_settings(&mut self, ui: &mut egui::Ui) {
+        ui.label("対象ディレクトリ:");
+        ui.add_space(5.0);
+
+        let mut removed_root = None;
+        let mut settings_changed = false;
+        let mut rescan_needed = false;
+
+        for (i, root) in self.settings.library_roots.iter_mut().enumerate() {
+            ui.push_id(("library_root", i), |ui| {
+                ui.horizontal(|ui| {
+                    ui.add_space(10.0);
+                    if ui.text_edit_singleline(&mut root.path).lost_focus() {
+                        settings_changed = true;
+                        rescan_needed = true;
+                    }
+                    if ui.checkbox(&mut root.follow_symlinks, "シンボリックリンクをたどる").changed() {
+                        settings_changed = true;
+                        rescan_needed = true;
+                    }
+                    if ui.button("削除").clicked() {
+                        removed_root = Some(i);
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.add_space(10.0);
+                    ui.label("除外パターン:");
+                    // 1行に1パターン（入力中の空行も保持し、スキャン時に無視する）
+                    let mut patterns = root.exclude_patterns.join("\n");
+                    let response = ui.add(
+                        egui::TextEdit::multiline(&mut patterns)
+                            .desired_rows(1)
+                            .hint_text("**/_incoming/**")
+                    );
+                    if response.changed() {
+                        root.exclude_patterns = patterns.split('\n').map(str::to_string).collect();
+                    }
+                    if response.lost_focus() {
+                        settings_changed = true;
+                        rescan_needed = true;
+                    }
+                });
+            });
+            ui.add_space(5.0);
+        }
+
+        if let Some(i) = removed_root {
+            self.settings.library_roots.remove(i);
+            settings_changed = true;
+            rescan_needed = true;
+        }
+
+        ui.horizontal(|ui| {
+            ui.add_space(10.0);
+            if ui.button("フォルダを追加").clicked() {
+                if let Some(path) = rfd::FileDialog::new().pick_folder() {
+                    let path = path.display().to_string();
+                    if !self.settings.library_roots.iter().any(|root| root.path == path) {
+                        self.settings.library_roots.push(crate::settings::LibraryRoot::new(path));
+                        settings_changed = true;
+                        rescan_needed = true;
+                    }
+                }
+            }
+        });
+
+        if settings_changed {
+            self.save_settings();
+        }
+        if rescan_needed {
+            self.refresh_music_library();
+        }
+    }
 }
\ No newline at end of file
@@ -1,5 +1,6 @@
 pub mod app;
 pub mod debug_ui;
+pub mod lyrics;
 pub mod music;
 pub mod player;
 pub mod playlist;
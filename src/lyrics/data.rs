@@ -0,0 +1,128 @@
+use std::path::PathBuf;
+
+/// 拡張LRC（A2形式）の単語単位タイミング
+#[derive(Debug, Clone, PartialEq)]
+pub struct LyricWord {
+    pub timestamp_ms: u64,     // 単語の開始時刻
+    pub end_ms: Option<u64>,   // 単語の終了時刻（次のタグがない場合はNone）
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LyricLine {
+    pub timestamp_ms: Option<u64>, // タイムスタンプ（同期なしの場合はNone）
+    pub text: String,              // 歌詞テキスト（単語タグ除去済み）
+    pub words: Vec<LyricWord>,     // 単語単位タイミング（拡張LRCの場合のみ）
+}
+
+impl LyricLine {
+    pub fn new(timestamp_ms: Option<u64>, text: String) -> Self {
+        Self {
+            timestamp_ms,
+            text,
+            words: Vec::new(),
+        }
+    }
+
+    pub fn has_word_timing(&self) -> bool {
+        !self.words.is_empty()
+    }
+}
+
+/// LRCのIDタグ情報
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LyricsMetadata {
+    pub title: Option<String>,   // [ti:]
+    pub artist: Option<String>,  // [ar:]
+    pub album: Option<String>,   // [al:]
+    pub author: Option<String>,  // [by:]
+    pub length: Option<String>,  // [length:]
+    pub other_tags: Vec<(String, String)>, // 表示には使わないタグ（[re:]・[ve:]・[#:]など、出現順）
+}
+
+/// 歌詞の読み込み元
+#[derive(Debug, Clone, PartialEq)]
+pub enum LyricsSource {
+    LrcFile(PathBuf),  // 楽曲と同名の.lrcファイル
+    Embedded(String),  // 音声ファイルのタグ（フィールド名）
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Lyrics {
+    pub source: Option<LyricsSource>,
+    pub metadata: LyricsMetadata,
+    pub offset_ms: i64,          // [offset:] 正の値で歌詞を早める
+    pub lines: Vec<LyricLine>,   // 歌詞行一覧（同期ありの場合は時間順ソート）
+}
+
+impl Lyrics {
+    /// タイムスタンプ付きの行を含むかどうか
+    pub fn is_synced(&self) -> bool {
+        self.lines.iter().any(|line| line.timestamp_ms.is_some())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// オフセット適用後の時刻
+    pub fn apply_offset(&self, timestamp_ms: u64) -> u64 {
+        (timestamp_ms as i64 - self.offset_ms).max(0) as u64
+    }
+
+    /// オフセット適用後の行の表示時刻
+    pub fn effective_time_ms(&self, line: &LyricLine) -> Option<u64> {
+        line.timestamp_ms.map(|timestamp| self.apply_offset(timestamp))
+    }
+
+    /// 再生位置に対応する現在行のインデックスを取得
+    pub fn find_current_line_index(&self, position_ms: u64) -> Option<usize> {
+        let mut current = None;
+        for (index, line) in self.lines.iter().enumerate() {
+            if let Some(time_ms) = self.effective_time_ms(line) {
+                if time_ms <= position_ms {
+                    current = Some(index);
+                } else {
+                    break;
+                }
+            }
+        }
+        current
+    }
+
+    /// 単語単位タイミングのある行で、再生位置までに歌い終えた文字数を取得
+    ///
+    /// 単語の途中の場合は経過時間に応じて文字数を按分する。
+    pub fn sung_char_count(&self, line_index: usize, position_ms: u64) -> usize {
+        let line = match self.lines.get(line_index) {
+            Some(line) => line,
+            None => return 0,
+        };
+
+        // 最後の単語の終了時刻が不明な場合は次の行の開始時刻を使う
+        let next_line_ms = self.lines[line_index + 1..].iter()
+            .find_map(|l| self.effective_time_ms(l));
+
+        let mut count = 0;
+        for (index, word) in line.words.iter().enumerate() {
+            let start = self.apply_offset(word.timestamp_ms);
+            let end = word.end_ms
+                .or_else(|| line.words.get(index + 1).map(|w| w.timestamp_ms))
+                .map(|end| self.apply_offset(end))
+                .or(next_line_ms)
+                .unwrap_or(start);
+            let word_chars = word.text.chars().count();
+
+            if position_ms >= end {
+                count += word_chars;
+            } else if position_ms > start {
+                let progress = (position_ms - start) as f64 / (end - start) as f64;
+                count += (word_chars as f64 * progress).floor() as usize;
+                break;
+            } else {
+                break;
+            }
+        }
+        count
+    }
+}
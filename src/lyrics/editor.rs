@@ -0,0 +1,186 @@
+use super::data::{LyricLine, Lyrics};
+use super::parser::LrcParser;
+use std::path::{Path, PathBuf};
+
+const MAX_UNDO_HISTORY: usize = 100;
+
+/// 元に戻す用の編集前スナップショット
+#[derive(Debug, Clone)]
+struct EditSnapshot {
+    lines: Vec<LyricLine>,
+    next_index: usize,
+}
+
+/// タップ同期によるLRCタイミング編集
+///
+/// 再生しながら各行の開始タイミングで打刻し、行ごとに微調整して保存する。
+#[derive(Debug, Clone)]
+pub struct LyricsSyncEditor {
+    track_path: PathBuf,
+    lyrics: Lyrics,          // 編集中の歌詞（IDタグ・オフセットは元の歌詞を引き継ぐ）
+    next_index: usize,       // 次に打刻する行
+    undo_stack: Vec<EditSnapshot>,
+    pub text_input: String,  // 歌詞テキストの入力欄
+}
+
+impl LyricsSyncEditor {
+    pub fn new(track_path: &Path, source: Option<&Lyrics>) -> Self {
+        let lyrics = source.cloned().unwrap_or_default();
+        Self {
+            track_path: track_path.to_path_buf(),
+            text_input: lyrics.lines.iter()
+                .map(|line| line.text.as_str())
+                .collect::<Vec<_>>()
+                .join("\n"),
+            lyrics,
+            next_index: 0,
+            undo_stack: Vec::new(),
+        }
+    }
+
+    pub fn track_path(&self) -> &Path {
+        &self.track_path
+    }
+
+    pub fn lines(&self) -> &[LyricLine] {
+        &self.lyrics.lines
+    }
+
+    pub fn next_index(&self) -> usize {
+        self.next_index
+    }
+
+    /// 歌詞テキストの入力中かどうか（行が未取り込みの状態）
+    pub fn is_text_input(&self) -> bool {
+        self.lyrics.lines.is_empty()
+    }
+
+    /// 入力欄のテキストを行に取り込む（LRC形式ならタイムスタンプも引き継ぐ）
+    pub fn import_text(&mut self) {
+        let parsed = LrcParser::parse(&self.text_input);
+        if parsed.is_empty() {
+            return;
+        }
+
+        self.push_undo();
+        self.lyrics.lines = parsed.lines;
+        if parsed.offset_ms != 0 {
+            self.lyrics.offset_ms = parsed.offset_ms;
+        }
+        self.next_index = 0;
+    }
+
+    /// 行を破棄してテキスト入力に戻る
+    pub fn back_to_text_input(&mut self) {
+        self.text_input = self.lyrics.lines.iter()
+            .map(|line| line.text.as_str())
+            .collect::<Vec<_>>()
+            .join("\n");
+        self.push_undo();
+        self.lyrics.lines.clear();
+        self.next_index = 0;
+    }
+
+    /// 次の行に再生位置を打刻してカーソルを進める
+    pub fn stamp_next(&mut self, position_ms: u64) -> bool {
+        if self.next_index >= self.lyrics.lines.len() {
+            return false;
+        }
+
+        // 表示時はオフセットが差し引かれるため、保存値には加算しておく
+        let timestamp_ms = (position_ms as i64 + self.lyrics.offset_ms).max(0) as u64;
+
+        self.push_undo();
+        let index = self.next_index;
+        Self::set_line_timestamp(&mut self.lyrics.lines[index], timestamp_ms);
+        self.next_index += 1;
+        true
+    }
+
+    /// 指定行のタイムスタンプを微調整（単語単位タイミングも同じだけずらす）
+    pub fn nudge(&mut self, index: usize, delta_ms: i64) -> bool {
+        let current = match self.lyrics.lines.get(index).and_then(|line| line.timestamp_ms) {
+            Some(timestamp_ms) => timestamp_ms,
+            None => return false,
+        };
+
+        self.push_undo();
+        let timestamp_ms = (current as i64 + delta_ms).max(0) as u64;
+        Self::set_line_timestamp(&mut self.lyrics.lines[index], timestamp_ms);
+        true
+    }
+
+    /// 指定行のタイムスタンプを削除
+    pub fn clear_timestamp(&mut self, index: usize) {
+        if self.lyrics.lines.get(index).is_some_and(|line| line.timestamp_ms.is_some()) {
+            self.push_undo();
+            let line = &mut self.lyrics.lines[index];
+            line.timestamp_ms = None;
+            line.words.clear();
+        }
+    }
+
+    /// 次に打刻する行を変更
+    pub fn set_next_index(&mut self, index: usize) {
+        self.next_index = index.min(self.lyrics.lines.len());
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    pub fn undo(&mut self) -> bool {
+        match self.undo_stack.pop() {
+            Some(snapshot) => {
+                self.lyrics.lines = snapshot.lines;
+                self.next_index = snapshot.next_index;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// タイムスタンプ未設定の行数
+    pub fn unstamped_count(&self) -> usize {
+        self.lyrics.lines.iter().filter(|line| line.timestamp_ms.is_none()).count()
+    }
+
+    /// 保存できるかどうか（未設定の行を残して保存すると、その行の歌詞が失われるため全行の打刻が必要）
+    pub fn can_save(&self) -> bool {
+        !self.is_text_input() && self.unstamped_count() == 0
+    }
+
+    /// 保存用の歌詞（タイムスタンプ順に並べ替え）
+    pub fn to_lyrics(&self) -> Lyrics {
+        let mut lyrics = self.lyrics.clone();
+        lyrics.source = None;
+        lyrics.lines.retain(|line| line.timestamp_ms.is_some());
+        lyrics.lines.sort_by_key(|line| line.timestamp_ms);
+        lyrics
+    }
+
+    fn set_line_timestamp(line: &mut LyricLine, timestamp_ms: u64) {
+        match line.timestamp_ms {
+            Some(previous) => {
+                let delta = timestamp_ms as i64 - previous as i64;
+                for word in &mut line.words {
+                    word.timestamp_ms = (word.timestamp_ms as i64 + delta).max(0) as u64;
+                    word.end_ms = word.end_ms.map(|end| (end as i64 + delta).max(0) as u64);
+                }
+            }
+            // 基準のない単語タイミングは信頼できないため破棄
+            None => line.words.clear(),
+        }
+        line.timestamp_ms = Some(timestamp_ms);
+    }
+
+    fn push_undo(&mut self) {
+        self.undo_stack.push(EditSnapshot {
+            lines: self.lyrics.lines.clone(),
+            next_index: self.next_index,
+        });
+        if self.undo_stack.len() > MAX_UNDO_HISTORY {
+            self.undo_stack.remove(0);
+        }
+    }
+}
@@ -0,0 +1,97 @@
+use super::data::{Lyrics, LyricsSource};
+use super::parser::LrcParser;
+use crate::music::EmbeddedLyrics;
+use crate::music::metadata::get_embedded_lyrics;
+use std::path::{Path, PathBuf};
+
+pub struct LyricsLoader;
+
+impl LyricsLoader {
+    /// 楽曲ファイルと同じディレクトリ・同じファイル名（拡張子違い）のLRCファイルを探す
+    pub fn find_lrc_file(track_path: &Path) -> Option<PathBuf> {
+        let stem = track_path.file_stem()?;
+        let directory = track_path.parent()?;
+
+        // 完全一致（.lrc / .LRC）を優先
+        for extension in ["lrc", "LRC"] {
+            let candidate = Self::sidecar_path(track_path, extension)?;
+            if candidate.is_file() {
+                return Some(candidate);
+            }
+        }
+
+        // 大文字小文字の異なる拡張子（.Lrc等）にも対応
+        let entries = std::fs::read_dir(directory).ok()?;
+        entries
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .find(|path| {
+                path.is_file()
+                    && path.file_stem() == Some(stem)
+                    && path.extension()
+                        .is_some_and(|ext| ext.to_string_lossy().eq_ignore_ascii_case("lrc"))
+            })
+    }
+
+    /// 新規作成時のLRCファイルパス（楽曲と同じディレクトリ・同じファイル名）
+    pub fn default_lrc_path(track_path: &Path) -> Option<PathBuf> {
+        Self::sidecar_path(track_path, "lrc")
+    }
+
+    fn sidecar_path(track_path: &Path, extension: &str) -> Option<PathBuf> {
+        let stem = track_path.file_stem()?;
+        let directory = track_path.parent()?;
+
+        // with_extensionは"01. Title"のようなファイル名で誤動作するため手動で連結
+        let mut file_name = stem.to_os_string();
+        file_name.push(".");
+        file_name.push(extension);
+        Some(directory.join(file_name))
+    }
+
+    /// 楽曲に対応する歌詞を読み込む（LRCファイルを優先し、なければ埋め込み歌詞）
+    pub fn load_for_track(track_path: &Path) -> Option<Lyrics> {
+        if let Some(lrc_path) = Self::find_lrc_file(track_path) {
+            match Self::load_from_file(&lrc_path) {
+                Ok(lyrics) if !lyrics.is_empty() => return Some(lyrics),
+                Ok(_) => {}
+                Err(e) => eprintln!("Warning: {}", e),
+            }
+        }
+
+        Self::load_embedded(track_path)
+    }
+
+    /// 音声ファイルの埋め込み歌詞を読み込む（同期歌詞を含むフィールドを優先）
+    pub fn load_embedded(track_path: &Path) -> Option<Lyrics> {
+        Self::select_embedded(get_embedded_lyrics(track_path))
+    }
+
+    /// 埋め込み歌詞の候補から表示に使うものを選択
+    pub fn select_embedded(candidates: Vec<EmbeddedLyrics>) -> Option<Lyrics> {
+        let mut parsed: Vec<Lyrics> = candidates.into_iter()
+            .map(|embedded| {
+                let mut lyrics = LrcParser::parse(&embedded.text);
+                lyrics.source = Some(LyricsSource::Embedded(embedded.field_name));
+                lyrics
+            })
+            .filter(|lyrics| !lyrics.is_empty())
+            .collect();
+
+        match parsed.iter().position(|lyrics| lyrics.is_synced()) {
+            Some(index) => Some(parsed.swap_remove(index)),
+            None => parsed.into_iter().next(),
+        }
+    }
+
+    pub fn load_from_file(lrc_path: &Path) -> Result<Lyrics, String> {
+        let bytes = std::fs::read(lrc_path)
+            .map_err(|e| format!("Failed to read LRC file '{}': {}", lrc_path.display(), e))?;
+
+        // UTF-8以外の文字コードは置換文字で読み込む
+        let content = String::from_utf8_lossy(&bytes);
+        let mut lyrics = LrcParser::parse(&content);
+        lyrics.source = Some(LyricsSource::LrcFile(lrc_path.to_path_buf()));
+        Ok(lyrics)
+    }
+}
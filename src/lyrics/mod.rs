@@ -0,0 +1,12 @@
+pub mod data;
+pub mod editor;
+pub mod loader;
+pub mod offsets;
+pub mod parser;
+pub mod writer;
+
+pub use data::{Lyrics, LyricsSource};
+pub use editor::LyricsSyncEditor;
+pub use loader::LyricsLoader;
+pub use offsets::LyricsOffsetManager;
+pub use writer::LrcWriter;
\ No newline at end of file
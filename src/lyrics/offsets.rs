@@ -0,0 +1,93 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LyricsOffsetsData {
+    pub version: String,
+    pub tracks: HashMap<PathBuf, i64>,
+}
+
+/// 楽曲ごとの歌詞タイミング補正（LRCの[offset:]に加算して適用）
+pub struct LyricsOffsetManager {
+    track_offsets: HashMap<PathBuf, i64>, // メモリ常駐データ（ミリ秒、正の値で歌詞を早める）
+    offsets_file: PathBuf,                // 単一JSONファイルパス
+}
+
+impl LyricsOffsetManager {
+    pub fn new() -> Self {
+        Self::with_file_path(Self::get_offsets_file_path())
+    }
+
+    pub fn with_file_path(offsets_file: PathBuf) -> Self {
+        Self {
+            track_offsets: HashMap::new(),
+            offsets_file,
+        }
+    }
+
+    pub fn get_offset(&self, track_path: &Path) -> i64 {
+        self.track_offsets.get(track_path).copied().unwrap_or(0)
+    }
+
+    /// オフセットを設定（0の場合は削除してファイルを肥大化させない）
+    pub fn set_offset(&mut self, track_path: &Path, offset_ms: i64) {
+        if offset_ms == 0 {
+            self.track_offsets.remove(track_path);
+        } else {
+            self.track_offsets.insert(track_path.to_path_buf(), offset_ms);
+        }
+    }
+
+    /// オフセットを増減し、変更後の値を返す
+    pub fn adjust_offset(&mut self, track_path: &Path, delta_ms: i64) -> i64 {
+        let offset_ms = self.get_offset(track_path) + delta_ms;
+        self.set_offset(track_path, offset_ms);
+        offset_ms
+    }
+
+    // 永続化（単一JSONファイル + メモリ常駐）
+    pub fn save_to_file(&self) -> Result<(), String> {
+        let data = LyricsOffsetsData {
+            version: "1.0".to_string(),
+            tracks: self.track_offsets.clone(),
+        };
+
+        let json = serde_json::to_string_pretty(&data)
+            .map_err(|e| format!("Failed to serialize lyrics offsets data: {}", e))?;
+
+        std::fs::write(&self.offsets_file, json)
+            .map_err(|e| format!("Failed to write lyrics offsets file: {}", e))?;
+
+        Ok(())
+    }
+
+    pub fn load_from_file(&mut self) -> Result<(), String> {
+        if !self.offsets_file.exists() {
+            // ファイルが存在しない場合は空のデータで初期化
+            self.track_offsets = HashMap::new();
+            return Ok(());
+        }
+
+        let content = std::fs::read_to_string(&self.offsets_file)
+            .map_err(|e| format!("Failed to read lyrics offsets file: {}", e))?;
+
+        let data: LyricsOffsetsData = serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse lyrics offsets data: {}", e))?;
+
+        self.track_offsets = data.tracks;
+        Ok(())
+    }
+
+    // ファイルパス管理
+    fn get_offsets_file_path() -> PathBuf {
+        let mut path = std::env::current_exe()
+            .unwrap_or_else(|_| PathBuf::from("."))
+            .parent()
+            .unwrap_or(&PathBuf::from("."))
+            .to_path_buf();
+
+        path.push("lyrics_offsets.json");
+        path
+    }
+}
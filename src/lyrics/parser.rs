@@ -0,0 +1,193 @@
+use super::data::{LyricLine, LyricWord, Lyrics};
+
+pub struct LrcParser;
+
+impl LrcParser {
+    /// LRC形式のテキストを解析
+    ///
+    /// タイムスタンプ付きの行が1行でもあれば同期歌詞として扱い、
+    /// タイムスタンプのない行は無視する。1行もなければ全行をプレーンテキストとして保持する。
+    pub fn parse(content: &str) -> Lyrics {
+        let mut lyrics = Lyrics::default();
+        let mut timed_lines = Vec::new();
+        let mut plain_lines = Vec::new();
+
+        // UTF-8 BOMを除去
+        let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+
+        for raw_line in content.lines() {
+            let line = raw_line.trim_end_matches('\r');
+            let mut rest = line.trim_start();
+            let mut timestamps = Vec::new();
+            let mut is_tag_line = false;
+
+            // 行頭の [..] タグを順に読み取る
+            while rest.starts_with('[') {
+                let close = match rest.find(']') {
+                    Some(close) => close,
+                    None => break,
+                };
+                let tag = &rest[1..close];
+
+                if let Some(timestamp_ms) = Self::parse_timestamp(tag) {
+                    timestamps.push(timestamp_ms);
+                } else if Self::apply_id_tag(&mut lyrics, tag) {
+                    is_tag_line = true;
+                } else {
+                    // 不明なタグはテキストの一部として扱う
+                    break;
+                }
+
+                rest = &rest[close + 1..];
+            }
+
+            if !timestamps.is_empty() {
+                for timestamp_ms in timestamps {
+                    timed_lines.push(Self::parse_line_body(timestamp_ms, rest));
+                }
+            } else if !is_tag_line {
+                plain_lines.push(LyricLine::new(None, line.trim().to_string()));
+            }
+        }
+
+        if timed_lines.is_empty() {
+            // 前後の空行を取り除く
+            while plain_lines.first().is_some_and(|l| l.text.is_empty()) {
+                plain_lines.remove(0);
+            }
+            while plain_lines.last().is_some_and(|l| l.text.is_empty()) {
+                plain_lines.pop();
+            }
+            lyrics.lines = plain_lines;
+        } else {
+            // 複数タイムスタンプの行があるため時間順に並べ替え（同時刻は出現順を維持）
+            timed_lines.sort_by_key(|l| l.timestamp_ms);
+            lyrics.lines = timed_lines;
+        }
+
+        lyrics
+    }
+
+    /// 行タイムスタンプ以降の本文を解析（拡張LRCの <mm:ss.xx> 単語タグに対応）
+    fn parse_line_body(line_timestamp_ms: u64, body: &str) -> LyricLine {
+        let mut words: Vec<LyricWord> = Vec::new();
+        let mut word_start_ms = line_timestamp_ms;
+        let mut word_text = String::new();
+        let mut rest = body.trim();
+        let mut has_word_tag = false;
+
+        loop {
+            match Self::find_word_tag(rest) {
+                Some((tag_start, tag_end, tag_ms)) => {
+                    word_text.push_str(&rest[..tag_start]);
+                    // タグ直前までの単語を確定（タグの時刻が単語の終了時刻）
+                    if !word_text.is_empty() {
+                        words.push(LyricWord {
+                            timestamp_ms: word_start_ms,
+                            end_ms: Some(tag_ms),
+                            text: std::mem::take(&mut word_text),
+                        });
+                    }
+                    word_start_ms = tag_ms;
+                    has_word_tag = true;
+                    rest = &rest[tag_end..];
+                }
+                None => {
+                    word_text.push_str(rest);
+                    if !word_text.is_empty() {
+                        words.push(LyricWord {
+                            timestamp_ms: word_start_ms,
+                            end_ms: None,
+                            text: word_text,
+                        });
+                    }
+                    break;
+                }
+            }
+        }
+
+        if !has_word_tag {
+            return LyricLine::new(Some(line_timestamp_ms), body.trim().to_string());
+        }
+
+        let text = words.iter().map(|w| w.text.as_str()).collect::<String>();
+        LyricLine {
+            timestamp_ms: Some(line_timestamp_ms),
+            text,
+            words,
+        }
+    }
+
+    /// 最初の有効な <mm:ss.xx> タグの位置（開始, 終了の次）と時刻を探す
+    fn find_word_tag(text: &str) -> Option<(usize, usize, u64)> {
+        for (open, _) in text.match_indices('<') {
+            if let Some(close_offset) = text[open..].find('>') {
+                let close = open + close_offset;
+                if let Some(timestamp_ms) = Self::parse_timestamp(&text[open + 1..close]) {
+                    return Some((open, close + 1, timestamp_ms));
+                }
+            }
+        }
+        None
+    }
+
+    /// "mm:ss", "mm:ss.xx", "mm:ss.xxx", "mm:ss:xx" 形式のタイムスタンプをミリ秒に変換
+    pub fn parse_timestamp(tag: &str) -> Option<u64> {
+        let (minutes_str, rest) = tag.split_once(':')?;
+        let minutes: u64 = minutes_str.trim().parse().ok()?;
+
+        let (seconds_str, fraction_str) = match rest.find(['.', ':']) {
+            Some(pos) => (&rest[..pos], Some(&rest[pos + 1..])),
+            None => (rest, None),
+        };
+
+        if seconds_str.is_empty() || !seconds_str.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        let seconds: u64 = seconds_str.parse().ok()?;
+        if seconds >= 60 {
+            return None;
+        }
+
+        let millis = match fraction_str {
+            Some(fraction) => {
+                if fraction.is_empty() || !fraction.chars().all(|c| c.is_ascii_digit()) {
+                    return None;
+                }
+                // 小数部を3桁に揃える（"5" → 500, "34" → 340, "3456" → 345）
+                let digits: String = fraction.chars().chain(std::iter::repeat('0')).take(3).collect();
+                digits.parse::<u64>().ok()?
+            }
+            None => 0,
+        };
+
+        Some(minutes * 60_000 + seconds * 1000 + millis)
+    }
+
+    fn apply_id_tag(lyrics: &mut Lyrics, tag: &str) -> bool {
+        let (key, value) = match tag.split_once(':') {
+            Some(pair) => pair,
+            None => return false,
+        };
+        let key = key.trim();
+        let value = value.trim();
+        let to_option = |v: &str| if v.is_empty() { None } else { Some(v.to_string()) };
+
+        match key.to_lowercase().as_str() {
+            "ti" => lyrics.metadata.title = to_option(value),
+            "ar" => lyrics.metadata.artist = to_option(value),
+            "al" => lyrics.metadata.album = to_option(value),
+            "by" => lyrics.metadata.author = to_option(value),
+            "length" => lyrics.metadata.length = to_option(value),
+            "offset" => {
+                lyrics.offset_ms = value.trim_start_matches('+').parse::<i64>().unwrap_or(0);
+            }
+            // 表示には使わないが、保存し直す時のために残すタグ（[la:]など未知の英字のタグを含む）
+            other if other == "#" || (!other.is_empty() && other.chars().all(|c| c.is_ascii_alphabetic())) => {
+                lyrics.metadata.other_tags.push((key.to_string(), value.to_string()));
+            }
+            _ => return false,
+        }
+        true
+    }
+}
@@ -0,0 +1,187 @@
+use super::data::{LyricLine, Lyrics};
+use super::loader::LyricsLoader;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub struct LrcWriter;
+
+impl LrcWriter {
+    /// 歌詞をLRC形式の文字列に変換（タイムスタンプのない行は出力しない）
+    pub fn to_lrc_string(lyrics: &Lyrics) -> String {
+        let mut output = String::new();
+
+        let tags = [
+            ("ti", &lyrics.metadata.title),
+            ("ar", &lyrics.metadata.artist),
+            ("al", &lyrics.metadata.album),
+            ("by", &lyrics.metadata.author),
+            ("length", &lyrics.metadata.length),
+        ];
+        for (key, value) in tags {
+            if let Some(value) = value {
+                output.push_str(&format!("[{}:{}]\n", key, value));
+            }
+        }
+        for (key, value) in &lyrics.metadata.other_tags {
+            output.push_str(&format!("[{}:{}]\n", key, value));
+        }
+        if lyrics.offset_ms != 0 {
+            output.push_str(&format!("[offset:{:+}]\n", lyrics.offset_ms));
+        }
+
+        for line in &lyrics.lines {
+            if let Some(timestamp_ms) = line.timestamp_ms {
+                output.push_str(&format!("[{}]{}\n", Self::format_timestamp(timestamp_ms), Self::format_line_body(line)));
+            }
+        }
+
+        output
+    }
+
+    /// ミリ秒を "mm:ss.xx" 形式のタイムスタンプに変換
+    pub fn format_timestamp(timestamp_ms: u64) -> String {
+        let minutes = timestamp_ms / 60_000;
+        let seconds = (timestamp_ms % 60_000) / 1000;
+        let centiseconds = (timestamp_ms % 1000) / 10;
+        format!("{:02}:{:02}.{:02}", minutes, seconds, centiseconds)
+    }
+
+    /// 行の本文（単語単位タイミングがあれば拡張LRCの <mm:ss.xx> タグ付き）
+    fn format_line_body(line: &LyricLine) -> String {
+        if !line.has_word_timing() {
+            return line.text.clone();
+        }
+
+        let mut body = String::new();
+        for (index, word) in line.words.iter().enumerate() {
+            body.push_str(&format!("<{}>{}", Self::format_timestamp(word.timestamp_ms), word.text));
+
+            // 次の単語の開始と異なる終了時刻のみ明示
+            let next_start = line.words.get(index + 1).map(|w| w.timestamp_ms);
+            if let Some(end_ms) = word.end_ms {
+                if next_start != Some(end_ms) {
+                    body.push_str(&format!("<{}>", Self::format_timestamp(end_ms)));
+                }
+            }
+        }
+        body
+    }
+
+    /// 楽曲のLRCファイル（既存のものがなければ同名の.lrc）に保存し、保存先パスを返す
+    pub fn save_for_track(track_path: &Path, lyrics: &Lyrics) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        let lrc_path = LyricsLoader::find_lrc_file(track_path)
+            .or_else(|| LyricsLoader::default_lrc_path(track_path))
+            .ok_or_else(|| format!("Invalid track path: {}", track_path.display()))?;
+
+        Self::save_to_file(&lrc_path, lyrics)?;
+        Ok(lrc_path)
+    }
+
+    pub fn save_to_file(path: &Path, lyrics: &Lyrics) -> Result<(), Box<dyn std::error::Error>> {
+        Self::write_atomically(path, Self::to_lrc_string(lyrics).as_bytes())
+    }
+
+    /// LRCファイルの[offset:]タグにdelta_msを加える
+    ///
+    /// 他の行は文字コードや改行も含めて元のバイト列のまま残す。
+    pub fn adjust_offset_in_file(path: &Path, delta_ms: i64) -> Result<(), Box<dyn std::error::Error>> {
+        let content = fs::read(path)?;
+        Self::write_atomically(path, &Self::adjust_offset_tag(&content, delta_ms))
+    }
+
+    /// LRCの内容の[offset:]タグの値にdelta_msを加える（タグが無ければ先頭のタグ行の後に追加）
+    ///
+    /// タグ以外は変更しないため、UTF-8以外の文字コードのファイルにも使える。
+    pub fn adjust_offset_tag(content: &[u8], delta_ms: i64) -> Vec<u8> {
+        const OFFSET_KEY: &[u8] = b"[offset:";
+        let bom_len = if content.starts_with(&[0xEF, 0xBB, 0xBF]) { 3 } else { 0 };
+        let newline: &[u8] = if content.windows(2).any(|pair| pair == b"\r\n") { b"\r\n" } else { b"\n" };
+        let lines = Self::line_ranges(content, bom_len);
+
+        // 同じタグが複数ある場合は読み込み時と同じく最後のものが有効
+        let offset_value = lines.iter().rev().find_map(|&(start, end, _)| {
+            let line = &content[start..end];
+            if line.len() < OFFSET_KEY.len() || !line[..OFFSET_KEY.len()].eq_ignore_ascii_case(OFFSET_KEY) {
+                return None;
+            }
+            let close = line.iter().position(|&b| b == b']')?;
+            Some((start + OFFSET_KEY.len(), start + close))
+        });
+
+        let mut output = Vec::with_capacity(content.len() + 16);
+        match offset_value {
+            Some((value_start, value_end)) => {
+                let current_ms = std::str::from_utf8(&content[value_start..value_end]).ok()
+                    .and_then(|value| value.trim().trim_start_matches('+').parse::<i64>().ok())
+                    .unwrap_or(0);
+                output.extend_from_slice(&content[..value_start]);
+                output.extend_from_slice(format!("{:+}", current_ms + delta_ms).as_bytes());
+                output.extend_from_slice(&content[value_end..]);
+            }
+            None => {
+                // 先頭から続く[ti:]などのタグ行（空行を挟んでもよい）の最後の行の後に挿入
+                let is_tag_line = |line: &[u8]| {
+                    line.first() == Some(&b'[')
+                        && line.get(1).is_some_and(|b| !b.is_ascii_digit())
+                        && line.contains(&b']')
+                };
+                let insert_at = lines.iter()
+                    .take_while(|&&(start, end, _)| start == end || is_tag_line(&content[start..end]))
+                    .filter(|&&(start, end, _)| start < end)
+                    .last()
+                    .map_or(bom_len, |&(_, _, line_end)| line_end);
+
+                output.extend_from_slice(&content[..insert_at]);
+                if insert_at > bom_len && content[insert_at - 1] != b'\n' {
+                    output.extend_from_slice(newline);
+                }
+                output.extend_from_slice(format!("[offset:{:+}]", delta_ms).as_bytes());
+                output.extend_from_slice(newline);
+                output.extend_from_slice(&content[insert_at..]);
+            }
+        }
+        output
+    }
+
+    /// 各行の前後の空白と改行を除いた範囲と、改行を含めた行末の位置
+    fn line_ranges(content: &[u8], start: usize) -> Vec<(usize, usize, usize)> {
+        let mut ranges = Vec::new();
+        let mut line_start = start;
+        while line_start < content.len() {
+            let line_end = content[line_start..].iter()
+                .position(|&b| b == b'\n')
+                .map_or(content.len(), |i| line_start + i + 1);
+
+            let mut start = line_start;
+            let mut end = line_end;
+            while end > start && content[end - 1].is_ascii_whitespace() {
+                end -= 1;
+            }
+            while start < end && content[start].is_ascii_whitespace() {
+                start += 1;
+            }
+            ranges.push((start, end, line_end));
+            line_start = line_end;
+        }
+        ranges
+    }
+
+    /// 一時ファイルに書き込んでから置換（原子的操作）
+    fn write_atomically(path: &Path, content: &[u8]) -> Result<(), Box<dyn std::error::Error>> {
+        let temp_path = format!("{}.tmp", path.display());
+        if let Err(e) = fs::write(&temp_path, content) {
+            eprintln!("Error: Failed to write temporary file '{}': {}", temp_path, e);
+            return Err(e.into());
+        }
+
+        // 一時ファイルを目的ファイルに移動
+        if let Err(e) = fs::rename(&temp_path, path) {
+            eprintln!("Error: Failed to move temporary file to '{}': {}", path.display(), e);
+            // クリーンアップ
+            let _ = fs::remove_file(&temp_path);
+            return Err(e.into());
+        }
+
+        Ok(())
+    }
+}
@@ -1,5 +1,6 @@
 mod app;
 mod debug_ui;
+mod lyrics;
 mod music;
 mod player;
 mod playlist;
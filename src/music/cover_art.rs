@@ -0,0 +1,50 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use glob::Pattern;
+use super::format::read_tags;
+
+/// カバーアートとして使うフォルダ内の画像の拡張子
+const FOLDER_IMAGE_EXTENSIONS: [&str; 6] = ["jpg", "jpeg", "png", "gif", "bmp", "webp"];
+
+/// 埋め込み画像が無い場合に探すフォルダ内の画像のファイル名（拡張子を除く、優先順、`*`を使用可）
+pub fn default_folder_image_names() -> Vec<String> {
+    ["cover", "folder", "front", "albumart*"].iter().map(|name| name.to_string()).collect()
+}
+
+/// 楽曲の埋め込み画像の画像データを読み込む（表示する時にのみ読み、TrackInfoには保持しない）
+pub fn read_cover_art(path: &Path) -> Option<Vec<u8>> {
+    read_tags(path).ok()?
+        .cover_art()
+        .map(<[u8]>::to_vec)
+}
+
+/// フォルダ内でカバーアートとして使う画像を探す
+///
+/// namesの先に書かれたものを優先し、同じ名前に一致する画像が複数ある場合はファイル名順で選ぶ。
+/// ファイル名は大文字小文字を区別しない。
+pub fn find_folder_image(dir: &Path, names: &[String]) -> Option<PathBuf> {
+    let patterns: Vec<Pattern> = names.iter()
+        .map(|name| name.trim().to_lowercase())
+        .filter(|name| !name.is_empty())
+        .filter_map(|name| Pattern::new(&name).ok())
+        .collect();
+    if patterns.is_empty() {
+        return None;
+    }
+
+    fs::read_dir(dir).ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .filter_map(|path| {
+            let extension = path.extension()?.to_string_lossy().to_lowercase();
+            if !FOLDER_IMAGE_EXTENSIONS.contains(&extension.as_str()) {
+                return None;
+            }
+            let stem = path.file_stem()?.to_string_lossy().to_lowercase();
+            let priority = patterns.iter().position(|pattern| pattern.matches(&stem))?;
+            Some((priority, path))
+        })
+        .min()
+        .map(|(_, path)| path)
+}
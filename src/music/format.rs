@@ -0,0 +1,347 @@
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+use std::time::Duration;
+use symphonia::core::codecs::CodecParameters;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::{MetadataOptions, MetadataRevision, StandardTagKey, StandardVisualKey};
+use symphonia::core::probe::Hint;
+
+/// 対応する音声フォーマット（拡張子で判定）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AudioFormat {
+    Flac,
+    Mp3,
+    OggVorbis,
+    Opus,
+    Wav,
+    Aiff,
+    M4a,  // AAC / ALAC
+}
+
+impl AudioFormat {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_string_lossy().to_lowercase();
+        match extension.as_str() {
+            "flac" => Some(Self::Flac),
+            "mp3" => Some(Self::Mp3),
+            "ogg" | "oga" => Some(Self::OggVorbis),
+            "opus" => Some(Self::Opus),
+            "wav" | "wave" => Some(Self::Wav),
+            "aif" | "aiff" | "aifc" => Some(Self::Aiff),
+            "m4a" | "m4b" => Some(Self::M4a), // 動画を含み得る.mp4は対象外
+            _ => None,
+        }
+    }
+
+    /// 再生可能かどうか
+    ///
+    /// symphonia 0.5 にはOpusデコーダーがなく、libopus等のネイティブライブラリへの依存も
+    /// 追加しないため、Opusの再生は対応範囲外とする。Opusファイルは形式として認識するが
+    /// ライブラリには取り込まず、取り込まなかった件数を画面に表示する。
+    pub fn is_playable(self) -> bool {
+        !matches!(self, Self::Opus)
+    }
+
+    #[allow(dead_code)]
+    pub fn display_name(self) -> &'static str {
+        match self {
+            Self::Flac => "FLAC",
+            Self::Mp3 => "MP3",
+            Self::OggVorbis => "Ogg Vorbis",
+            Self::Opus => "Opus",
+            Self::Wav => "WAV",
+            Self::Aiff => "AIFF",
+            Self::M4a => "M4A",
+        }
+    }
+}
+
+/// ライブラリに取り込む（再生可能な）音声ファイルかどうか
+pub fn is_supported_audio_file(path: &Path) -> bool {
+    AudioFormat::from_path(path).is_some_and(|format| format.is_playable())
+}
+
+/// 形式は認識できるが再生できない（ライブラリに取り込まない）音声ファイルかどうか
+pub fn is_unplayable_audio_file(path: &Path) -> bool {
+    AudioFormat::from_path(path).is_some_and(|format| !format.is_playable())
+}
+
+/// 埋め込み画像の種類（カバーアート選択の優先度に使用）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PictureKind {
+    FrontCover,
+    Other,
+    Misc,
+}
+
+#[derive(Debug, Clone)]
+pub struct TagPicture {
+    pub kind: PictureKind,
+    pub data: Vec<u8>,
+}
+
+/// フォーマット共通のタグ情報
+///
+/// フィールド名はVorbisコメント名（大文字）に正規化する。
+/// ID3v2 / MP4 / RIFF 等のタグはsymphoniaの標準キーから対応する名前に変換する。
+#[derive(Debug, Clone, Default)]
+pub struct AudioTags {
+    pub fields: Vec<(String, String)>,
+    pub pictures: Vec<TagPicture>,
+    pub duration: Option<Duration>,
+}
+
+impl AudioTags {
+    /// 指定フィールドの最初の値
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.fields.iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(key))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// 指定フィールドの全ての値（同名フィールドが複数ある場合）
+    pub fn get_all<'a>(&'a self, key: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.fields.iter()
+            .filter(move |(name, _)| name.eq_ignore_ascii_case(key))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// カバーアート（表紙 → Other → 最初の画像の順に選択）
+    pub fn cover_art(&self) -> Option<&[u8]> {
+        self.pictures.iter()
+            .find(|pic| pic.kind == PictureKind::FrontCover)
+            .or_else(|| self.pictures.iter().find(|pic| pic.kind == PictureKind::Other))
+            .or_else(|| self.pictures.first())
+            .map(|pic| pic.data.as_slice())
+    }
+}
+
+/// 読み込むタグの範囲
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TagContent {
+    /// タグ・画像・再生時間
+    Full,
+    /// 画像を除くタグと再生時間
+    TextOnly,
+    /// 再生時間のみ
+    DurationOnly,
+}
+
+const FLAC_BLOCK_STREAMINFO: u8 = 0;
+const FLAC_BLOCK_VORBIS_COMMENT: u8 = 4;
+
+/// 音声ファイルのタグ・画像・再生時間を読み込む
+pub fn read_tags(path: &Path) -> Result<AudioTags, String> {
+    read_tag_content(path, TagContent::Full)
+}
+
+/// 画像を除いたタグと再生時間を読み込む（再生開始時のゲイン取得など画像が不要な場合）
+pub fn read_text_tags(path: &Path) -> Result<AudioTags, String> {
+    read_tag_content(path, TagContent::TextOnly)
+}
+
+/// 再生時間のみを取得（FLACはSTREAMINFO、その他はコーデック情報から取得し、タグは読まない）
+#[allow(dead_code)]
+pub fn read_duration(path: &Path) -> Option<Duration> {
+    read_tag_content(path, TagContent::DurationOnly).ok()?.duration
+}
+
+fn read_tag_content(path: &Path, content: TagContent) -> Result<AudioTags, String> {
+    match AudioFormat::from_path(path) {
+        Some(AudioFormat::Flac) => read_flac_tags(path, content),
+        Some(_) => read_symphonia_tags(path, content),
+        None => Err(format!("Unsupported audio format: {}", path.display())),
+    }
+}
+
+fn read_flac_tags(path: &Path, content: TagContent) -> Result<AudioTags, String> {
+    let tag = match content {
+        TagContent::Full => metaflac::Tag::read_from_path(path),
+        TagContent::TextOnly => read_flac_blocks(path, &[FLAC_BLOCK_STREAMINFO, FLAC_BLOCK_VORBIS_COMMENT]),
+        TagContent::DurationOnly => read_flac_blocks(path, &[FLAC_BLOCK_STREAMINFO]),
+    }
+    .map_err(|e| format!("Failed to read FLAC metadata from '{}': {}", path.display(), e))?;
+
+    let fields = tag.vorbis_comments()
+        .map(|comments| {
+            comments.comments.iter()
+                .flat_map(|(name, values)| {
+                    values.iter().map(move |value| (name.to_uppercase(), value.clone()))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let pictures = tag.pictures()
+        .map(|pic| TagPicture {
+            kind: match pic.picture_type {
+                metaflac::block::PictureType::CoverFront => PictureKind::FrontCover,
+                metaflac::block::PictureType::Other => PictureKind::Other,
+                _ => PictureKind::Misc,
+            },
+            data: pic.data.clone(),
+        })
+        .collect();
+
+    Ok(AudioTags {
+        fields,
+        pictures,
+        duration: flac_duration(&tag),
+    })
+}
+
+/// FLACのメタデータブロックのうち指定した種類のみを読み込む（画像等のブロックは読み飛ばす）
+fn read_flac_blocks(path: &Path, block_types: &[u8]) -> metaflac::Result<metaflac::Tag> {
+    let mut reader = BufReader::new(File::open(path)?);
+
+    let mut ident = [0; 4];
+    reader.read_exact(&mut ident)?;
+    // 先頭にID3v2タグが付いたファイルはタグを読み飛ばす（metaflacと同じ扱い）
+    if &ident[0..3] == b"ID3" {
+        let mut header_tail = [0; 6];
+        reader.read_exact(&mut header_tail)?;
+        let footer_size = if header_tail[1] & 0x10 != 0 { 10 } else { 0 };
+        let tag_size = header_tail[2..].iter().fold(0i64, |size, &byte| (size << 7) | (byte & 0x7f) as i64);
+        reader.seek_relative(tag_size + footer_size)?;
+        reader.read_exact(&mut ident)?;
+    }
+    if &ident != b"fLaC" {
+        return Err(metaflac::Error::new(metaflac::ErrorKind::InvalidInput, "reader does not contain flac metadata"));
+    }
+
+    let mut tag = metaflac::Tag::new();
+    loop {
+        let mut header = [0; 4];
+        reader.read_exact(&mut header)?;
+        let is_last = header[0] & 0x80 != 0;
+        let length = u32::from_be_bytes([0, header[1], header[2], header[3]]) as usize;
+        if block_types.contains(&(header[0] & 0x7f)) {
+            let mut data = header.to_vec();
+            data.resize(header.len() + length, 0);
+            reader.read_exact(&mut data[header.len()..])?;
+            let (_, _, block) = metaflac::Block::read_from(&mut data.as_slice())?;
+            tag.push_block(block);
+        } else {
+            reader.seek_relative(length as i64)?;
+        }
+        if is_last {
+            return Ok(tag);
+        }
+    }
+}
+
+fn flac_duration(tag: &metaflac::Tag) -> Option<Duration> {
+    let streaminfo = tag.get_streaminfo()?;
+    let total_samples = streaminfo.total_samples;
+    let sample_rate = streaminfo.sample_rate;
+    if total_samples > 0 && sample_rate > 0 {
+        Some(Duration::from_secs_f64(total_samples as f64 / sample_rate as f64))
+    } else {
+        None
+    }
+}
+
+fn read_symphonia_tags(path: &Path, content: TagContent) -> Result<AudioTags, String> {
+    let file = std::fs::File::open(path)
+        .map_err(|e| format!("Failed to open audio file '{}': {}", path.display(), e))?;
+    let stream = MediaSourceStream::new(Box::new(file), Default::default());
+
+    let mut hint = Hint::new();
+    if let Some(extension) = path.extension() {
+        hint.with_extension(&extension.to_string_lossy());
+    }
+
+    let mut probed = symphonia::default::get_probe()
+        .format(&hint, stream, &FormatOptions::default(), &MetadataOptions::default())
+        .map_err(|e| format!("Failed to read metadata from '{}': {}", path.display(), e))?;
+
+    let mut tags = AudioTags {
+        duration: probed.format.default_track().and_then(|track| codec_duration(&track.codec_params)),
+        ..Default::default()
+    };
+    if content == TagContent::DurationOnly {
+        return Ok(tags);
+    }
+
+    let with_pictures = content == TagContent::Full;
+    // コンテナ前方のタグ（MP3のID3v2等）
+    if let Some(mut metadata) = probed.metadata.get() {
+        if let Some(revision) = metadata.skip_to_latest() {
+            collect_revision(revision, &mut tags, with_pictures);
+        }
+    }
+    // コンテナ内のタグ（Vorbisコメント・MP4アトム・RIFF INFO等）
+    if let Some(revision) = probed.format.metadata().skip_to_latest() {
+        collect_revision(revision, &mut tags, with_pictures);
+    }
+
+    Ok(tags)
+}
+
+fn codec_duration(params: &CodecParameters) -> Option<Duration> {
+    let n_frames = params.n_frames?;
+    match (params.time_base, params.sample_rate) {
+        (Some(time_base), _) => {
+            let time = time_base.calc_time(n_frames);
+            Some(Duration::from_secs(time.seconds) + Duration::from_secs_f64(time.frac))
+        }
+        (None, Some(sample_rate)) if sample_rate > 0 => {
+            Some(Duration::from_secs_f64(n_frames as f64 / sample_rate as f64))
+        }
+        _ => None,
+    }
+}
+
+fn collect_revision(revision: &MetadataRevision, tags: &mut AudioTags, with_pictures: bool) {
+    for tag in revision.tags() {
+        let name = tag.std_key
+            .and_then(vorbis_field_name)
+            .map(|name| name.to_string())
+            // ID3v2のユーザー定義フレームは "TXXX:説明" の説明部分をフィールド名とする
+            .unwrap_or_else(|| tag.key.trim_start_matches("TXXX:").to_uppercase());
+        // RIFF INFO等はNUL終端・パディングが値に残るため除去
+        let value = tag.value.to_string().trim_end_matches('\0').to_string();
+        tags.fields.push((name, value));
+    }
+
+    if !with_pictures {
+        return;
+    }
+    for visual in revision.visuals() {
+        let kind = match visual.usage {
+            Some(StandardVisualKey::FrontCover) => PictureKind::FrontCover,
+            Some(StandardVisualKey::OtherIcon) | None => PictureKind::Other,
+            Some(_) => PictureKind::Misc,
+        };
+        tags.pictures.push(TagPicture {
+            kind,
+            data: visual.data.to_vec(),
+        });
+    }
+}
+
+/// symphoniaの標準キーに対応するVorbisコメント名
+fn vorbis_field_name(key: StandardTagKey) -> Option<&'static str> {
+    let name = match key {
+        StandardTagKey::TrackTitle => "TITLE",
+        StandardTagKey::Artist => "ARTIST",
+        StandardTagKey::AlbumArtist => "ALBUMARTIST",
+        StandardTagKey::Album => "ALBUM",
+        StandardTagKey::Composer => "COMPOSER",
+        StandardTagKey::Genre => "GENRE",
+        StandardTagKey::TrackNumber => "TRACKNUMBER",
+        StandardTagKey::TrackTotal => "TRACKTOTAL",
+        StandardTagKey::DiscNumber => "DISCNUMBER",
+        StandardTagKey::DiscTotal => "DISCTOTAL",
+        StandardTagKey::Date => "DATE",
+        StandardTagKey::Lyrics => "LYRICS",
+        StandardTagKey::ReplayGainTrackGain => "REPLAYGAIN_TRACK_GAIN",
+        StandardTagKey::ReplayGainTrackPeak => "REPLAYGAIN_TRACK_PEAK",
+        StandardTagKey::ReplayGainAlbumGain => "REPLAYGAIN_ALBUM_GAIN",
+        StandardTagKey::ReplayGainAlbumPeak => "REPLAYGAIN_ALBUM_PEAK",
+        _ => return None,
+    };
+    Some(name)
+}
@@ -1,14 +1,40 @@
-use super::metadata::{TrackInfo, get_flac_metadata, is_flac_file};
+use super::format::{is_supported_audio_file, is_unplayable_audio_file};
+use super::library_index::LibraryIndex;
+use super::library_roots::{collect_audio_files, collect_audio_files_in, unavailable_roots, AudioFiles, ScanRoot};
+use super::metadata::{TrackInfo, get_track_metadata};
+use super::replaygain::ReplayGain;
 use super::tree::{MusicTreeNode, MusicNodeType};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+/// ファイルの変更をライブラリに反映した結果
+#[derive(Debug, Default)]
+pub struct LibraryUpdate {
+    pub added: Vec<TrackInfo>,
+    pub updated: usize,
+    pub removed: usize,
+}
+
+impl LibraryUpdate {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.updated == 0 && self.removed == 0
+    }
+}
+
+/// 変更されたパスを読み直した結果
+#[derive(Debug, Default)]
+pub struct ChangedTracks {
+    pub paths: Vec<PathBuf>,                        // 変更されたファイルまたはディレクトリ
+    pub tracks: HashMap<PathBuf, Option<TrackInfo>>, // 変更されたパス以下にある楽曲（読めなかった場合はNone）
+}
 
 pub struct MusicLibrary {
     tracks: Vec<TrackInfo>,
     tree: Vec<MusicTreeNode>,
     original_tree: Vec<MusicTreeNode>,
     classical_composer_hierarchy: bool,
+    index: Option<LibraryIndex>,  // タグ情報のディスクキャッシュ（Noneの場合は毎回全ファイルを読む）
 }
 
 impl MusicLibrary {
@@ -18,18 +44,105 @@ impl MusicLibrary {
             tree: Vec::new(),
             original_tree: Vec::new(),
             classical_composer_hierarchy,
+            index: None,
         }
     }
 
+    /// インデックスを使い、変更されたファイルのみタグを読み直すライブラリ
+    pub fn with_index(classical_composer_hierarchy: bool, index: LibraryIndex) -> Self {
+        Self {
+            index: Some(index),
+            ..Self::new(classical_composer_hierarchy)
+        }
+    }
+
+    #[allow(dead_code)]
     pub fn scan_directory(&mut self, path: &Path) {
-        self.tracks.clear();
-        if path.exists() && path.is_dir() {
-            self.collect_tracks_recursive(path);
+        self.scan_roots(&[ScanRoot::new(path)]);
+    }
+
+    /// 全ての音楽フォルダを除外ルールに従ってスキャン
+    #[allow(dead_code)]
+    pub fn scan_roots(&mut self, roots: &[ScanRoot]) {
+        let mut files = AudioFiles::default();
+        collect_audio_files(roots, &mut files, &mut |_, _| true);
+        if !files.unplayable.is_empty() {
+            eprintln!("Warning: Skipped {} audio files in formats that cannot be played", files.unplayable.len());
+        }
+        let files = files.playable;
+        let tracks = files.iter()
+            .filter_map(|file| read_track(self.index.as_mut(), file))
+            .collect();
+        if let Some(index) = &mut self.index {
+            index.retain_paths(&files.into_iter().collect(), &unavailable_roots(roots));
+        }
+        self.save_index();
+        self.set_tracks(tracks);
+    }
+
+    /// スキャン結果の楽曲でツリーを作り直す
+    pub fn set_tracks(&mut self, tracks: Vec<TrackInfo>) {
+        self.tracks = tracks;
+        self.build_tree();
+
+        // Step 4-3: スキャン後にメモリ最適化
+        self.optimize_memory();
+    }
+
+    /// 変更されたパスを読み直した結果をツリーに反映
+    ///
+    /// ファイルの読み込みは済んでいるため、ファイルシステムにはアクセスしない。
+    pub fn apply_changes(&mut self, changes: ChangedTracks) -> LibraryUpdate {
+        let ChangedTracks { paths, mut tracks } = changes;
+        let changed_paths: HashSet<PathBuf> = paths.into_iter().collect();
+        let mut update = LibraryUpdate::default();
+
+        // 変更されたパス以下の楽曲は、読み直した楽曲で置き換えるか取り除く
+        let mut kept = Vec::with_capacity(self.tracks.len());
+        for track in std::mem::take(&mut self.tracks) {
+            if !track.path.ancestors().any(|ancestor| changed_paths.contains(ancestor)) {
+                kept.push(track);
+                continue;
+            }
+            match tracks.remove(&track.path) {
+                Some(Some(reread)) => {
+                    kept.push(reread);
+                    update.updated += 1;
+                }
+                // 削除されたファイルと、コピー途中などで読めないファイル（読めるようになった時に追加する）
+                Some(None) | None => update.removed += 1,
+            }
+        }
+
+        // 残りは新しく見つかった楽曲
+        let mut added: Vec<TrackInfo> = tracks.into_values().flatten().collect();
+        added.sort_by(|a, b| a.path.cmp(&b.path));
+        kept.extend(added.iter().cloned());
+        self.tracks = kept;
+        update.added = added;
+
+        if !update.is_empty() {
             self.build_tree();
-            
-            // Step 4-3: スキャン後にメモリ最適化
-            self.optimize_memory();
         }
+        update
+    }
+
+    /// インデックスに変更があれば保存
+    pub fn save_index(&mut self) {
+        if let Some(index) = &mut self.index {
+            if let Err(e) = index.save_to_file() {
+                eprintln!("Warning: Failed to save library index: {}", e);
+            }
+        }
+    }
+
+    /// バックグラウンドでのスキャン中はインデックスをスキャンスレッドに渡す
+    pub fn take_index(&mut self) -> Option<LibraryIndex> {
+        self.index.take()
+    }
+
+    pub fn set_index(&mut self, index: LibraryIndex) {
+        self.index = Some(index);
     }
 
     #[allow(dead_code)]
@@ -74,23 +187,36 @@ impl MusicLibrary {
         self.tracks.len()
     }
 
+    pub fn get_tracks(&self) -> &[TrackInfo] {
+        &self.tracks
+    }
 
-    fn collect_tracks_recursive(&mut self, path: &Path) {
-        if let Ok(entries) = fs::read_dir(path) {
-            for entry in entries.filter_map(|e| e.ok()) {
-                let entry_path = entry.path();
-                
-                if entry_path.is_dir() {
-                    self.collect_tracks_recursive(&entry_path);
-                } else if is_flac_file(&entry_path) {
-                    if let Some(track_info) = get_flac_metadata(&entry_path) {
-                        self.tracks.push(track_info);
-                    }
+    /// 解析したReplayGainを楽曲とツリーに反映（ツリーの展開状態は維持）
+    pub fn update_replay_gain(&mut self, gains: &HashMap<PathBuf, ReplayGain>) {
+        for track in &mut self.tracks {
+            if let Some(replay_gain) = gains.get(&track.path) {
+                track.replay_gain = Some(*replay_gain);
+            }
+        }
+        if let Some(index) = &mut self.index {
+            index.update_replay_gain(gains);
+        }
+        Self::update_tree_replay_gain(&mut self.tree, gains);
+        Self::update_tree_replay_gain(&mut self.original_tree, gains);
+    }
+
+    fn update_tree_replay_gain(nodes: &mut [MusicTreeNode], gains: &HashMap<PathBuf, ReplayGain>) {
+        for node in nodes {
+            if let Some(track) = &mut node.track_info {
+                if let Some(replay_gain) = gains.get(&track.path) {
+                    track.replay_gain = Some(*replay_gain);
                 }
             }
+            Self::update_tree_replay_gain(&mut node.children, gains);
         }
     }
 
+
     fn build_tree(&mut self) {
         if self.classical_composer_hierarchy {
             self.original_tree = self.build_composer_based_tree();
@@ -207,10 +333,11 @@ impl MusicLibrary {
         let mut artist_map: HashMap<String, HashMap<String, Vec<TrackInfo>>> = HashMap::new();
         
         for track in tracks {
+            let (artist, album) = album_group_key(&track);
             artist_map
-                .entry(track.album_artist.clone().unwrap_or_else(|| track.artist.clone()))
+                .entry(artist)
                 .or_default()
-                .entry(track.album.clone())
+                .entry(album)
                 .or_default()
                 .push(track);
         }
@@ -373,4 +500,66 @@ impl MusicLibrary {
             }
         }
     }
-}
\ No newline at end of file
+}
+
+/// 追加・削除・名前変更・更新されたファイルまたはディレクトリ以下の楽曲を読み直す
+///
+/// 除外されたパスや、どの音楽フォルダにも含まれないパスの楽曲は読まない。
+/// インデックスからは、変更されたパス以下で見つからなくなったファイルを取り除く。
+pub fn read_changed_tracks(roots: &[ScanRoot], paths: &[PathBuf], mut index: Option<&mut LibraryIndex>) -> ChangedTracks {
+    let mut files = AudioFiles::default();
+    for changed_path in paths {
+        let Some(root) = roots.iter().find(|root| root.includes(changed_path)) else {
+            continue;
+        };
+        if changed_path.is_dir() {
+            collect_audio_files_in(root, changed_path, &mut files);
+        } else if changed_path.is_file() && is_supported_audio_file(changed_path) {
+            files.playable.push(changed_path.clone());
+        } else if changed_path.is_file() && is_unplayable_audio_file(changed_path) {
+            files.unplayable.push(changed_path.clone());
+        }
+    }
+    for file in &files.unplayable {
+        eprintln!("Warning: Skipped audio file in a format that cannot be played: {}", file.display());
+    }
+
+    let tracks: HashMap<PathBuf, Option<TrackInfo>> = files.playable.into_iter()
+        .map(|file| {
+            let track = read_track(index.as_deref_mut(), &file);
+            (file, track)
+        })
+        .collect();
+    if let Some(index) = index {
+        let found: HashSet<PathBuf> = tracks.keys().cloned().collect();
+        index.retain_paths_under(paths, &found);
+    }
+
+    ChangedTracks {
+        paths: paths.to_vec(),
+        tracks,
+    }
+}
+
+/// 楽曲情報を取得（インデックスにあり、ファイルが変わっていなければタグを読み直さない）
+pub fn read_track(index: Option<&mut LibraryIndex>, path: &Path) -> Option<TrackInfo> {
+    let Some(index) = index else {
+        return get_track_metadata(path);
+    };
+    let metadata = fs::metadata(path).ok()?;
+    if let Some(track) = index.get_unchanged(path, &metadata) {
+        return Some(track);
+    }
+
+    let track = get_track_metadata(path)?;
+    index.insert(&track, &metadata);
+    Some(track)
+}
+
+/// アルバムのグループ化キー（アルバムアーティスト、無い場合はアーティスト + アルバム名）
+pub fn album_group_key(track: &TrackInfo) -> (String, String) {
+    (
+        track.album_artist.clone().unwrap_or_else(|| track.artist.clone()),
+        track.album.clone(),
+    )
+}
@@ -0,0 +1,161 @@
+use super::metadata::TrackInfo;
+use super::replaygain::ReplayGain;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+const INDEX_VERSION: &str = "1.0";
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LibraryIndexData {
+    pub version: String,
+    pub entries: Vec<LibraryIndexEntry>,
+}
+
+/// 読み込み時点のファイルのサイズ・更新日時と、解析済みのタグ
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LibraryIndexEntry {
+    pub size: u64,
+    pub modified: Option<SystemTime>,
+    pub track: TrackInfo,
+}
+
+/// ライブラリのタグ情報のディスクキャッシュ
+///
+/// 再スキャン時はサイズと更新日時が変わっていないファイルのタグを読み直さずに再利用する。
+pub struct LibraryIndex {
+    entries: HashMap<PathBuf, LibraryIndexEntry>, // メモリ常駐データ
+    index_file: PathBuf,                          // 単一JSONファイルパス
+    modified: bool,                               // 読み込み後に内容が変わったかどうか
+}
+
+impl LibraryIndex {
+    pub fn new() -> Self {
+        Self::with_file_path(Self::get_index_file_path())
+    }
+
+    pub fn with_file_path(index_file: PathBuf) -> Self {
+        Self {
+            entries: HashMap::new(),
+            index_file,
+            modified: false,
+        }
+    }
+
+    /// ファイルが前回の読み込みから変わっていなければキャッシュした楽曲情報を返す
+    pub fn get_unchanged(&self, path: &Path, metadata: &fs::Metadata) -> Option<TrackInfo> {
+        let entry = self.entries.get(path)?;
+        let unchanged = entry.size == metadata.len() && entry.modified == metadata.modified().ok();
+        unchanged.then(|| entry.track.clone())
+    }
+
+    /// 読み込んだ楽曲情報を記録
+    pub fn insert(&mut self, track: &TrackInfo, metadata: &fs::Metadata) {
+        self.entries.insert(track.path.clone(), LibraryIndexEntry {
+            size: metadata.len(),
+            modified: metadata.modified().ok(),
+            track: track.clone(),
+        });
+        self.modified = true;
+    }
+
+    /// スキャンで見つからなかったファイル（削除・移動されたもの）を取り除く
+    ///
+    /// 接続されていないドライブなど、スキャンできなかったフォルダ以下のエントリは残す。
+    pub fn retain_paths(&mut self, paths: &HashSet<PathBuf>, unavailable_dirs: &[PathBuf]) {
+        let count = self.entries.len();
+        self.entries.retain(|path, _| {
+            paths.contains(path) || unavailable_dirs.iter().any(|dir| path.starts_with(dir))
+        });
+        if self.entries.len() != count {
+            self.modified = true;
+        }
+    }
+
+    /// 解析したReplayGainを記録（タグに書き込まない設定でも次回起動時に解析し直さないようにする）
+    pub fn update_replay_gain(&mut self, gains: &HashMap<PathBuf, ReplayGain>) {
+        for (path, replay_gain) in gains {
+            if let Some(entry) = self.entries.get_mut(path) {
+                entry.track.replay_gain = Some(*replay_gain);
+                self.modified = true;
+            }
+        }
+    }
+
+    /// 変更されたパス以下のエントリのうち、読み直した時に見つからなかったものを取り除く
+    pub fn retain_paths_under(&mut self, changed_paths: &[PathBuf], paths: &HashSet<PathBuf>) {
+        let count = self.entries.len();
+        self.entries.retain(|path, _| {
+            paths.contains(path) || !changed_paths.iter().any(|changed_path| path.starts_with(changed_path))
+        });
+        if self.entries.len() != count {
+            self.modified = true;
+        }
+    }
+
+    // 永続化（単一JSONファイル + メモリ常駐）
+    pub fn save_to_file(&mut self) -> Result<(), String> {
+        if !self.modified {
+            return Ok(());
+        }
+
+        let data = LibraryIndexData {
+            version: INDEX_VERSION.to_string(),
+            entries: self.entries.values().cloned().collect(),
+        };
+
+        // 楽曲数が多いと大きくなるため整形せずに保存
+        let json = serde_json::to_string(&data)
+            .map_err(|e| format!("Failed to serialize library index: {}", e))?;
+
+        // 一時ファイルに書き込んでから置換（原子的操作）
+        let temp_path = format!("{}.tmp", self.index_file.display());
+        std::fs::write(&temp_path, json)
+            .map_err(|e| format!("Failed to write temporary library index file '{}': {}", temp_path, e))?;
+        if let Err(e) = std::fs::rename(&temp_path, &self.index_file) {
+            // クリーンアップ
+            let _ = std::fs::remove_file(&temp_path);
+            return Err(format!("Failed to move temporary file to '{}': {}", self.index_file.display(), e));
+        }
+
+        self.modified = false;
+        Ok(())
+    }
+
+    pub fn load_from_file(&mut self) -> Result<(), String> {
+        self.entries.clear();
+        self.modified = false;
+        if !self.index_file.exists() {
+            return Ok(());
+        }
+
+        let content = std::fs::read_to_string(&self.index_file)
+            .map_err(|e| format!("Failed to read library index file: {}", e))?;
+
+        let data: LibraryIndexData = serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse library index: {}", e))?;
+
+        // 形式が異なる場合は全ファイルを読み直す
+        if data.version != INDEX_VERSION {
+            return Ok(());
+        }
+
+        self.entries = data.entries.into_iter()
+            .map(|entry| (entry.track.path.clone(), entry))
+            .collect();
+        Ok(())
+    }
+
+    // ファイルパス管理
+    fn get_index_file_path() -> PathBuf {
+        let mut path = std::env::current_exe()
+            .unwrap_or_else(|_| PathBuf::from("."))
+            .parent()
+            .unwrap_or_else(|| Path::new("."))
+            .to_path_buf();
+        path.push("library_index.json");
+        path
+    }
+}
@@ -0,0 +1,169 @@
+use super::format::{is_supported_audio_file, is_unplayable_audio_file};
+use crate::settings::LibraryRoot;
+use glob::{MatchOptions, Pattern};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 除外パターンの照合方法（`*`はパス区切りをまたがず、`**`は任意の階層に一致）
+const EXCLUDE_MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: !cfg!(windows),
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// スキャン対象の音楽フォルダと除外ルール
+#[derive(Debug, Clone)]
+pub struct ScanRoot {
+    pub path: PathBuf,
+    excludes: Vec<Pattern>,
+    follow_symlinks: bool,
+}
+
+impl ScanRoot {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            excludes: Vec::new(),
+            follow_symlinks: false,
+        }
+    }
+
+    /// 設定の音楽フォルダ（不正な除外パターンは警告して無視する）
+    pub fn from_settings(root: &LibraryRoot) -> Self {
+        let mut scan_root = Self::new(&root.path).with_follow_symlinks(root.follow_symlinks);
+        for pattern in &root.exclude_patterns {
+            if let Err(e) = scan_root.add_exclude(pattern) {
+                eprintln!("Warning: {}", e);
+            }
+        }
+        scan_root
+    }
+
+    pub fn with_follow_symlinks(mut self, follow_symlinks: bool) -> Self {
+        self.follow_symlinks = follow_symlinks;
+        self
+    }
+
+    /// 除外パターンを追加（空白のみのパターンは無視する）
+    pub fn add_exclude(&mut self, pattern: &str) -> Result<(), String> {
+        let pattern = pattern.trim();
+        if pattern.is_empty() {
+            return Ok(());
+        }
+        let pattern = Pattern::new(pattern)
+            .map_err(|e| format!("Invalid exclude pattern '{}': {}", pattern, e))?;
+        self.excludes.push(pattern);
+        Ok(())
+    }
+
+    /// pathがこのフォルダ以下にあり、途中のディレクトリも含めて除外されていないかどうか
+    pub fn includes(&self, path: &Path) -> bool {
+        let Ok(relative) = path.strip_prefix(&self.path) else {
+            return false;
+        };
+        let mut ancestor = PathBuf::new();
+        let mut components = relative.components().peekable();
+        while let Some(component) = components.next() {
+            ancestor.push(component);
+            let is_dir = components.peek().is_some() || path.is_dir();
+            if self.is_excluded_relative(&ancestor, is_dir) {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// ルートからの相対パスが除外パターンに一致するかどうか
+    fn is_excluded_relative(&self, relative: &Path, is_dir: bool) -> bool {
+        if self.excludes.is_empty() {
+            return false;
+        }
+        // OSに依らず`/`区切りで照合する
+        let relative = relative.components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        self.excludes.iter().any(|pattern| {
+            pattern.matches_with(&relative, EXCLUDE_MATCH_OPTIONS)
+                // `**/_incoming/**`のようにディレクトリ以下を指定したパターンでディレクトリごと除外する
+                || (is_dir && pattern.matches_with(&format!("{}/", relative), EXCLUDE_MATCH_OPTIONS))
+        })
+    }
+}
+
+/// 列挙した音声ファイル
+#[derive(Debug, Default)]
+pub struct AudioFiles {
+    pub playable: Vec<PathBuf>,
+    pub unplayable: Vec<PathBuf>, // 形式は認識できるがデコーダーが無いため取り込まないファイル（Opus）
+}
+
+/// 接続されていないドライブなど、スキャンできなかった音楽フォルダ
+pub fn unavailable_roots(roots: &[ScanRoot]) -> Vec<PathBuf> {
+    roots.iter()
+        .filter(|root| !root.path.is_dir())
+        .map(|root| root.path.clone())
+        .collect()
+}
+
+/// 全ての音楽フォルダ以下の対応形式のファイルを列挙
+///
+/// ディレクトリに入るたびにon_directoryをそれまでに見つかった再生可能なファイル数とともに呼び出し、
+/// falseが返された場合は列挙を中止してfalseを返す。
+/// シンボリックリンクのループや重なったフォルダは、実体が同じディレクトリを一度だけ読むことで避ける。
+pub fn collect_audio_files(
+    roots: &[ScanRoot],
+    files: &mut AudioFiles,
+    on_directory: &mut dyn FnMut(&Path, usize) -> bool,
+) -> bool {
+    let mut visited = HashSet::new();
+    roots.iter().all(|root| collect_recursive(root, &root.path, &mut visited, files, on_directory))
+}
+
+/// 音楽フォルダ内の一部のディレクトリ以下の対応形式のファイルを列挙
+pub fn collect_audio_files_in(root: &ScanRoot, dir: &Path, files: &mut AudioFiles) {
+    collect_recursive(root, dir, &mut HashSet::new(), files, &mut |_, _| true);
+}
+
+fn collect_recursive(
+    root: &ScanRoot,
+    dir: &Path,
+    visited: &mut HashSet<PathBuf>,
+    files: &mut AudioFiles,
+    on_directory: &mut dyn FnMut(&Path, usize) -> bool,
+) -> bool {
+    let canonical = fs::canonicalize(dir).unwrap_or_else(|_| dir.to_path_buf());
+    if !visited.insert(canonical) {
+        return true;
+    }
+    if !on_directory(dir, files.playable.len()) {
+        return false;
+    }
+
+    if let Ok(entries) = fs::read_dir(dir) {
+        for entry in entries.filter_map(|e| e.ok()) {
+            let entry_path = entry.path();
+            let Ok(relative) = entry_path.strip_prefix(&root.path) else {
+                continue;
+            };
+
+            if entry_path.is_dir() {
+                let is_symlink = entry.file_type().is_ok_and(|file_type| file_type.is_symlink());
+                if (is_symlink && !root.follow_symlinks) || root.is_excluded_relative(relative, true) {
+                    continue;
+                }
+                if !collect_recursive(root, &entry_path, visited, files, on_directory) {
+                    return false;
+                }
+            } else if root.is_excluded_relative(relative, false) {
+                continue;
+            } else if is_supported_audio_file(&entry_path) {
+                files.playable.push(entry_path);
+            } else if is_unplayable_audio_file(&entry_path) {
+                files.unplayable.push(entry_path);
+            }
+        }
+    }
+    true
+}
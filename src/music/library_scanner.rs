@@ -0,0 +1,221 @@
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::thread;
+use super::library::{read_changed_tracks, read_track, ChangedTracks};
+use super::library_index::LibraryIndex;
+use super::library_roots::{collect_audio_files, unavailable_roots, AudioFiles, ScanRoot};
+use super::metadata::TrackInfo;
+
+/// スキャンスレッドからUIへの通知
+enum LibraryScanEvent {
+    DirectoryStarted(PathBuf, usize), // 列挙中のディレクトリと、それまでに見つかったファイル数
+    FilesListed(usize, Vec<PathBuf>), // 再生可能なファイル数と、再生できない形式のため取り込まないファイル
+    TrackRead(PathBuf, Option<Box<TrackInfo>>),
+    ChangesRead(ChangedTracks),
+    Finished(Option<LibraryIndex>),
+}
+
+/// スキャンの進捗
+#[derive(Debug, Clone, Default)]
+pub struct LibraryScanProgress {
+    pub files_found: usize,
+    pub total: Option<usize>,              // ファイルの列挙が終わるまではNone
+    pub completed: usize,
+    pub current_directory: Option<PathBuf>,
+    pub unplayable_files: Vec<PathBuf>,    // Opusなど再生できない形式のため取り込まなかったファイル
+    pub changes_only: bool,                // 音楽フォルダの監視で通知された変更のみを読み直している
+    pub cancelled: bool,
+    pub finished: bool,
+}
+
+/// スキャンの結果
+pub struct LibraryScanResult {
+    pub tracks: Option<Vec<TrackInfo>>,   // 中止した場合と、変更のみを読み直した場合はNone
+    pub changes: Option<ChangedTracks>,   // 変更のみを読み直した場合の結果
+    pub index: Option<LibraryIndex>,      // スキャンスレッドに渡したインデックス
+}
+
+/// 音楽フォルダをバックグラウンドでスキャンするジョブ
+///
+/// スキャン中もライブラリの既存のツリーはそのまま使え、完了時に結果をまとめて反映する。
+pub struct LibraryScanner {
+    receiver: Option<Receiver<LibraryScanEvent>>,
+    cancel: Arc<AtomicBool>,
+    progress: LibraryScanProgress,
+    tracks: Vec<TrackInfo>,
+    changes: Option<ChangedTracks>,
+}
+
+impl LibraryScanner {
+    pub fn new() -> Self {
+        Self {
+            receiver: None,
+            cancel: Arc::new(AtomicBool::new(false)),
+            progress: LibraryScanProgress::default(),
+            tracks: Vec::new(),
+            changes: None,
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.receiver.is_some()
+    }
+
+    pub fn get_progress(&self) -> &LibraryScanProgress {
+        &self.progress
+    }
+
+    /// スキャンを開始（indexがある場合は変更されていないファイルのタグを読み直さない）
+    pub fn start(&mut self, roots: Vec<ScanRoot>, index: Option<LibraryIndex>) {
+        if self.is_running() {
+            return;
+        }
+
+        let (sender, receiver) = mpsc::channel();
+        self.cancel = Arc::new(AtomicBool::new(false));
+        self.progress = LibraryScanProgress::default();
+        self.tracks = Vec::new();
+        self.changes = None;
+        self.receiver = Some(receiver);
+
+        let cancel = Arc::clone(&self.cancel);
+        thread::spawn(move || scan_library(&roots, index, &cancel, &sender));
+    }
+
+    /// 変更されたファイルまたはディレクトリ以下のみを読み直す
+    ///
+    /// 前回のスキャンで取り込まなかったファイルの一覧は残す。
+    pub fn start_changes(&mut self, roots: Vec<ScanRoot>, paths: Vec<PathBuf>, mut index: Option<LibraryIndex>) {
+        if self.is_running() {
+            return;
+        }
+
+        let (sender, receiver) = mpsc::channel();
+        self.cancel = Arc::new(AtomicBool::new(false));
+        self.progress = LibraryScanProgress {
+            unplayable_files: std::mem::take(&mut self.progress.unplayable_files),
+            changes_only: true,
+            ..LibraryScanProgress::default()
+        };
+        self.tracks = Vec::new();
+        self.changes = None;
+        self.receiver = Some(receiver);
+
+        thread::spawn(move || {
+            let changes = read_changed_tracks(&roots, &paths, index.as_mut());
+            if let Some(index) = &mut index {
+                if let Err(e) = index.save_to_file() {
+                    eprintln!("Warning: Failed to save library index: {}", e);
+                }
+            }
+            let _ = sender.send(LibraryScanEvent::ChangesRead(changes));
+            let _ = sender.send(LibraryScanEvent::Finished(index));
+        });
+    }
+
+    pub fn cancel(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+        self.progress.cancelled = true;
+    }
+
+    /// スキャンスレッドからの通知を反映し、完了した場合は結果を返す
+    pub fn poll(&mut self) -> Option<LibraryScanResult> {
+        let receiver = self.receiver.as_ref()?;
+
+        let mut finished = false;
+        let mut index = None;
+        loop {
+            let event = match receiver.try_recv() {
+                Ok(event) => event,
+                Err(mpsc::TryRecvError::Empty) => break,
+                // 送信側が終了した場合（パニック時を含む）も完了とする
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    finished = true;
+                    break;
+                }
+            };
+            match event {
+                LibraryScanEvent::DirectoryStarted(directory, files_found) => {
+                    self.progress.current_directory = Some(directory);
+                    self.progress.files_found = files_found;
+                }
+                LibraryScanEvent::FilesListed(total, unplayable_files) => {
+                    self.progress.files_found = total;
+                    self.progress.total = Some(total);
+                    self.progress.unplayable_files = unplayable_files;
+                }
+                LibraryScanEvent::TrackRead(path, track) => {
+                    self.progress.completed += 1;
+                    self.progress.current_directory = path.parent().map(Path::to_path_buf);
+                    self.tracks.extend(track.map(|track| *track));
+                }
+                LibraryScanEvent::ChangesRead(changes) => {
+                    self.changes = Some(changes);
+                }
+                LibraryScanEvent::Finished(returned_index) => {
+                    index = returned_index;
+                    finished = true;
+                }
+            }
+        }
+
+        if !finished {
+            return None;
+        }
+
+        self.receiver = None;
+        self.progress.current_directory = None;
+        self.progress.finished = true;
+        let tracks = std::mem::take(&mut self.tracks);
+        Some(LibraryScanResult {
+            tracks: (!self.progress.cancelled && !self.progress.changes_only).then_some(tracks),
+            changes: self.changes.take(),
+            index,
+        })
+    }
+}
+
+fn scan_library(roots: &[ScanRoot], mut index: Option<LibraryIndex>, cancel: &AtomicBool, sender: &Sender<LibraryScanEvent>) {
+    let completed = scan_files(roots, index.as_mut(), cancel, sender);
+
+    // 中止した場合は見つからなかったファイルを判断できないため、インデックスは整理しない
+    if let (true, Some(index)) = (completed, &mut index) {
+        if let Err(e) = index.save_to_file() {
+            eprintln!("Warning: Failed to save library index: {}", e);
+        }
+    }
+
+    let _ = sender.send(LibraryScanEvent::Finished(index));
+}
+
+/// ファイルを列挙してからタグを読み込む（中止した場合はfalse）
+fn scan_files(roots: &[ScanRoot], mut index: Option<&mut LibraryIndex>, cancel: &AtomicBool, sender: &Sender<LibraryScanEvent>) -> bool {
+    let mut files = AudioFiles::default();
+    let listed = collect_audio_files(roots, &mut files, &mut |directory, files_found| {
+        let _ = sender.send(LibraryScanEvent::DirectoryStarted(directory.to_path_buf(), files_found));
+        !cancel.load(Ordering::Relaxed)
+    });
+    if !listed {
+        return false;
+    }
+    let files = {
+        let AudioFiles { playable, unplayable } = files;
+        let _ = sender.send(LibraryScanEvent::FilesListed(playable.len(), unplayable));
+        playable
+    };
+
+    for path in &files {
+        if cancel.load(Ordering::Relaxed) {
+            return false;
+        }
+        let track = read_track(index.as_deref_mut(), path);
+        let _ = sender.send(LibraryScanEvent::TrackRead(path.clone(), track.map(Box::new)));
+    }
+
+    if let Some(index) = index {
+        index.retain_paths(&files.into_iter().collect(), &unavailable_roots(roots));
+    }
+    true
+}
@@ -0,0 +1,141 @@
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver};
+use std::time::{Duration, Instant};
+
+/// 最後の変更からこの時間だけ変更が無ければ、まとめてライブラリに反映する
+pub const LIBRARY_WATCH_DEBOUNCE: Duration = Duration::from_millis(1500);
+
+/// まとめた変更
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LibraryChanges {
+    pub paths: Vec<PathBuf>, // 追加・削除・名前変更・更新されたファイルまたはディレクトリ
+    pub rescan: bool,        // 取りこぼしがあり得るため全体を再スキャンする必要がある
+}
+
+/// 変更の通知を集め、一定時間変更が無くなったらまとめて返す
+///
+/// フォルダのコピーなどで大量の通知が続く間は反映を待つ。
+#[derive(Debug, Default)]
+pub struct PendingChanges {
+    paths: HashSet<PathBuf>,
+    rescan: bool,
+    last_event_at: Option<Instant>,
+}
+
+impl PendingChanges {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, paths: impl IntoIterator<Item = PathBuf>, now: Instant) {
+        self.paths.extend(paths);
+        self.last_event_at = Some(now);
+    }
+
+    pub fn record_rescan(&mut self, now: Instant) {
+        self.rescan = true;
+        self.last_event_at = Some(now);
+    }
+
+    /// 最後の変更からdebounce以上経っていれば、集めた変更を返す
+    pub fn take_settled(&mut self, now: Instant, debounce: Duration) -> Option<LibraryChanges> {
+        let last_event_at = self.last_event_at?;
+        if now.saturating_duration_since(last_event_at) < debounce {
+            return None;
+        }
+
+        self.last_event_at = None;
+        let mut paths: Vec<PathBuf> = self.paths.drain().collect();
+        paths.sort();
+        Some(LibraryChanges {
+            paths,
+            rescan: std::mem::take(&mut self.rescan),
+        })
+    }
+}
+
+/// 音楽フォルダ以下のファイルの変更を監視する
+pub struct LibraryWatcher {
+    watcher: Option<RecommendedWatcher>,
+    receiver: Option<Receiver<notify::Result<Event>>>,
+    roots: Vec<PathBuf>,
+    pending: PendingChanges,
+}
+
+impl LibraryWatcher {
+    pub fn new() -> Self {
+        Self {
+            watcher: None,
+            receiver: None,
+            roots: Vec::new(),
+            pending: PendingChanges::new(),
+        }
+    }
+
+    pub fn is_watching(&self) -> bool {
+        self.watcher.is_some()
+    }
+
+    /// 音楽フォルダの監視を開始（既に同じフォルダを監視している場合は何もしない）
+    ///
+    /// 監視できないフォルダがあった場合も、残りのフォルダは監視してエラーを返す。
+    pub fn watch(&mut self, roots: &[PathBuf]) -> Result<(), String> {
+        if self.is_watching() && self.roots == roots {
+            return Ok(());
+        }
+        self.unwatch();
+        if roots.is_empty() {
+            return Ok(());
+        }
+
+        let (sender, receiver) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(move |result| {
+            let _ = sender.send(result);
+        }).map_err(|e| format!("Failed to create library watcher: {}", e))?;
+        let errors: Vec<String> = roots.iter()
+            .filter_map(|root| {
+                watcher.watch(root, RecursiveMode::Recursive).err()
+                    .map(|e| format!("Failed to watch {}: {}", root.display(), e))
+            })
+            .collect();
+
+        self.watcher = Some(watcher);
+        self.receiver = Some(receiver);
+        self.roots = roots.to_vec();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors.join(", "))
+        }
+    }
+
+    pub fn unwatch(&mut self) {
+        self.watcher = None;
+        self.receiver = None;
+        self.roots.clear();
+        self.pending = PendingChanges::new();
+    }
+
+    /// 通知を取り込み、変更が落ち着いていればまとめて返す
+    pub fn poll(&mut self) -> Option<LibraryChanges> {
+        let receiver = self.receiver.as_ref()?;
+        let now = Instant::now();
+
+        while let Ok(result) = receiver.try_recv() {
+            match result {
+                Ok(event) if event.need_rescan() => self.pending.record_rescan(now),
+                // 読み取りのみの通知は無視
+                Ok(event) if matches!(event.kind, EventKind::Access(_)) => {}
+                Ok(event) => self.pending.record(event.paths, now),
+                Err(e) => {
+                    eprintln!("Warning: Library watcher error: {}", e);
+                    self.pending.record_rescan(now);
+                }
+            }
+        }
+
+        self.pending.take_settled(now, LIBRARY_WATCH_DEBOUNCE)
+    }
+}
@@ -0,0 +1,296 @@
+use std::collections::VecDeque;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::DecoderOptions;
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+/// ゲーティングブロック（400ms）を構成する100msサブブロックの数（75%オーバーラップ）
+const SUB_BLOCKS_PER_BLOCK: usize = 4;
+const ABSOLUTE_GATE_LUFS: f64 = -70.0;
+const RELATIVE_GATE_LU: f64 = -10.0;
+/// トゥルーピーク検出用の補間フィルタの位相あたりのタップ数
+const TRUE_PEAK_TAPS_PER_PHASE: usize = 12;
+
+/// 双2次フィルタ（転置直接II型）
+#[derive(Debug, Clone, Copy)]
+struct Biquad {
+    b: [f64; 3],
+    a: [f64; 3],
+    z: [f64; 2],
+}
+
+impl Biquad {
+    fn new(b: [f64; 3], a: [f64; 3]) -> Self {
+        Self { b, a, z: [0.0; 2] }
+    }
+
+    fn process(&mut self, x: f64) -> f64 {
+        let y = self.b[0] * x + self.z[0];
+        self.z[0] = self.b[1] * x - self.a[1] * y + self.z[1];
+        self.z[1] = self.b[2] * x - self.a[2] * y;
+        y
+    }
+}
+
+/// ITU-R BS.1770 のK特性フィルタ（高域シェルフ + RLB高域通過）を任意のサンプルレートで生成
+fn k_weighting_filters(sample_rate: f64) -> [Biquad; 2] {
+    let f0 = 1681.974450955533;
+    let gain_db = 3.999843853973347;
+    let q = 0.7071752369554196;
+    let k = (std::f64::consts::PI * f0 / sample_rate).tan();
+    let vh = 10f64.powf(gain_db / 20.0);
+    let vb = vh.powf(0.4996667741545416);
+    let a0 = 1.0 + k / q + k * k;
+    let shelf = Biquad::new(
+        [(vh + vb * k / q + k * k) / a0, 2.0 * (k * k - vh) / a0, (vh - vb * k / q + k * k) / a0],
+        [1.0, 2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+    );
+
+    let f0 = 38.13547087602444;
+    let q = 0.5003270373238773;
+    let k = (std::f64::consts::PI * f0 / sample_rate).tan();
+    let a0 = 1.0 + k / q + k * k;
+    let high_pass = Biquad::new(
+        [1.0, -2.0, 1.0],
+        [1.0, 2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+    );
+
+    [shelf, high_pass]
+}
+
+/// オーバーサンプリングによるトゥルーピーク検出
+#[derive(Debug, Clone)]
+struct TruePeakDetector {
+    factor: usize,
+    coefficients: Vec<f64>,  // 位相pのj番目のタップは coefficients[j * factor + p]
+    history: VecDeque<f64>,  // 直近の入力（先頭が最新）
+    peak: f64,
+}
+
+impl TruePeakDetector {
+    fn new(sample_rate: u32) -> Self {
+        let factor = match sample_rate {
+            0..=95_999 => 4,
+            96_000..=191_999 => 2,
+            _ => 1,
+        };
+
+        // 元のナイキスト周波数を遮断周波数とする窓付きsinc補間フィルタ
+        let length = TRUE_PEAK_TAPS_PER_PHASE * factor;
+        let center = (length - 1) as f64 / 2.0;
+        let coefficients = (0..length)
+            .map(|n| {
+                let t = (n as f64 - center) / factor as f64;
+                let sinc = if t == 0.0 {
+                    1.0
+                } else {
+                    (std::f64::consts::PI * t).sin() / (std::f64::consts::PI * t)
+                };
+                let window = 0.5 - 0.5 * (2.0 * std::f64::consts::PI * (n as f64 + 0.5) / length as f64).cos();
+                sinc * window
+            })
+            .collect();
+
+        Self {
+            factor,
+            coefficients,
+            history: VecDeque::from(vec![0.0; TRUE_PEAK_TAPS_PER_PHASE]),
+            peak: 0.0,
+        }
+    }
+
+    fn process(&mut self, x: f64) {
+        self.peak = self.peak.max(x.abs());
+        if self.factor == 1 {
+            return;
+        }
+
+        self.history.pop_back();
+        self.history.push_front(x);
+        for phase in 0..self.factor {
+            let interpolated: f64 = self.history.iter()
+                .enumerate()
+                .map(|(tap, sample)| sample * self.coefficients[tap * self.factor + phase])
+                .sum();
+            self.peak = self.peak.max(interpolated.abs());
+        }
+    }
+}
+
+/// ITU-R BS.1770 / EBU R128 に基づくラウドネス測定
+pub struct LoudnessMeter {
+    channels: usize,
+    channel_weights: Vec<f64>,
+    filters: Vec<[Biquad; 2]>,
+    true_peaks: Vec<TruePeakDetector>,
+    sub_block_frames: usize,
+    sub_block_energy: f64,
+    sub_block_position: usize,
+    recent_sub_blocks: VecDeque<f64>,
+    block_energies: Vec<f64>,
+}
+
+impl LoudnessMeter {
+    pub fn new(sample_rate: u32, channels: usize) -> Self {
+        let channels = channels.max(1);
+        // 5.1ch以上の場合はLFEを除外し、サラウンドチャンネルを+1.5dBで重み付け
+        let channel_weights = (0..channels)
+            .map(|channel| match (channels >= 6, channel) {
+                (true, 3) => 0.0,
+                (true, 4) | (true, 5) => 1.41,
+                _ => 1.0,
+            })
+            .collect();
+
+        Self {
+            channels,
+            channel_weights,
+            filters: vec![k_weighting_filters(sample_rate as f64); channels],
+            true_peaks: vec![TruePeakDetector::new(sample_rate); channels],
+            sub_block_frames: (sample_rate as usize / 10).max(1),
+            sub_block_energy: 0.0,
+            sub_block_position: 0,
+            recent_sub_blocks: VecDeque::with_capacity(SUB_BLOCKS_PER_BLOCK),
+            block_energies: Vec::new(),
+        }
+    }
+
+    /// インターリーブされたサンプルを追加
+    pub fn process_interleaved(&mut self, samples: &[f32]) {
+        for frame in samples.chunks_exact(self.channels) {
+            let mut energy = 0.0;
+            for (channel, &sample) in frame.iter().enumerate() {
+                let x = sample as f64;
+                self.true_peaks[channel].process(x);
+                let [shelf, high_pass] = &mut self.filters[channel];
+                let y = high_pass.process(shelf.process(x));
+                energy += self.channel_weights[channel] * y * y;
+            }
+
+            self.sub_block_energy += energy;
+            self.sub_block_position += 1;
+            if self.sub_block_position == self.sub_block_frames {
+                if self.recent_sub_blocks.len() == SUB_BLOCKS_PER_BLOCK {
+                    self.recent_sub_blocks.pop_front();
+                }
+                self.recent_sub_blocks.push_back(self.sub_block_energy);
+                self.sub_block_energy = 0.0;
+                self.sub_block_position = 0;
+
+                if self.recent_sub_blocks.len() == SUB_BLOCKS_PER_BLOCK {
+                    let block_frames = (self.sub_block_frames * SUB_BLOCKS_PER_BLOCK) as f64;
+                    self.block_energies.push(self.recent_sub_blocks.iter().sum::<f64>() / block_frames);
+                }
+            }
+        }
+    }
+
+    pub fn finish(self) -> TrackLoudness {
+        TrackLoudness {
+            true_peak: self.true_peaks.iter().map(|detector| detector.peak).fold(0.0, f64::max),
+            block_energies: self.block_energies,
+        }
+    }
+}
+
+/// 1曲分の測定結果（アルバム全体のゲーティングに使うためブロック単位の値を保持）
+#[derive(Debug, Clone)]
+pub struct TrackLoudness {
+    block_energies: Vec<f64>,
+    pub true_peak: f64,  // リニア値
+}
+
+impl TrackLoudness {
+    /// 統合ラウドネス（LUFS）。無音などでゲートを通るブロックが無い場合はNone
+    pub fn integrated_lufs(&self) -> Option<f64> {
+        gated_loudness(&[self])
+    }
+}
+
+/// アルバム全体の統合ラウドネス（全曲のブロックをまとめてゲーティング）とピーク
+pub fn album_loudness(tracks: &[&TrackLoudness]) -> (Option<f64>, f64) {
+    let peak = tracks.iter().map(|track| track.true_peak).fold(0.0, f64::max);
+    (gated_loudness(tracks), peak)
+}
+
+fn gated_loudness(tracks: &[&TrackLoudness]) -> Option<f64> {
+    let blocks = || tracks.iter().flat_map(|track| track.block_energies.iter().copied());
+    let mean_loudness = |threshold: f64| {
+        let (sum, count) = blocks()
+            .filter(|&energy| energy_to_lufs(energy) > threshold)
+            .fold((0.0, 0usize), |(sum, count), energy| (sum + energy, count + 1));
+        (count > 0).then(|| energy_to_lufs(sum / count as f64))
+    };
+
+    let relative_gate = mean_loudness(ABSOLUTE_GATE_LUFS)? + RELATIVE_GATE_LU;
+    mean_loudness(relative_gate.max(ABSOLUTE_GATE_LUFS))
+}
+
+fn energy_to_lufs(energy: f64) -> f64 {
+    -0.691 + 10.0 * energy.log10()
+}
+
+/// 音声ファイルをデコードしてラウドネスを測定（cancelがtrueになった時点で中断）
+pub fn analyze_file(path: &Path, cancel: &AtomicBool) -> Result<TrackLoudness, String> {
+    let file = std::fs::File::open(path)
+        .map_err(|e| format!("Failed to open audio file '{}': {}", path.display(), e))?;
+    let stream = MediaSourceStream::new(Box::new(file), Default::default());
+
+    let mut hint = Hint::new();
+    if let Some(extension) = path.extension() {
+        hint.with_extension(&extension.to_string_lossy());
+    }
+
+    let mut format = symphonia::default::get_probe()
+        .format(&hint, stream, &FormatOptions::default(), &MetadataOptions::default())
+        .map_err(|e| format!("Failed to read '{}': {}", path.display(), e))?
+        .format;
+    let track = format.default_track()
+        .ok_or_else(|| format!("No audio track in '{}'", path.display()))?;
+    let track_id = track.id;
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .map_err(|e| format!("Failed to create decoder for '{}': {}", path.display(), e))?;
+
+    let mut meter: Option<LoudnessMeter> = None;
+    let mut sample_buffer: Option<SampleBuffer<f32>> = None;
+
+    loop {
+        if cancel.load(Ordering::Relaxed) {
+            return Err("Cancelled".to_string());
+        }
+
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(SymphoniaError::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(format!("Failed to read '{}': {}", path.display(), e)),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            // 破損したパケットは読み飛ばす
+            Err(SymphoniaError::DecodeError(_)) => continue,
+            Err(e) => return Err(format!("Failed to decode '{}': {}", path.display(), e)),
+        };
+
+        let spec = *decoded.spec();
+        let meter = meter.get_or_insert_with(|| LoudnessMeter::new(spec.rate, spec.channels.count()));
+        let buffer = sample_buffer.get_or_insert_with(|| SampleBuffer::new(decoded.capacity() as u64, spec));
+        if buffer.capacity() < decoded.capacity() * spec.channels.count() {
+            *buffer = SampleBuffer::new(decoded.capacity() as u64, spec);
+        }
+        buffer.copy_interleaved_ref(decoded);
+        meter.process_interleaved(buffer.samples());
+    }
+
+    meter.map(LoudnessMeter::finish)
+        .ok_or_else(|| format!("No audio data in '{}'", path.display()))
+}
@@ -1,5 +1,7 @@
 use std::path::{Path, PathBuf};
 use serde::{Deserialize, Serialize};
+use super::format::{is_supported_audio_file, read_tags};
+use super::replaygain::ReplayGain;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TrackInfo {
@@ -14,8 +16,9 @@ pub struct TrackInfo {
     pub disc_number: Option<u32>,
     pub disc_total: Option<u32>,
     pub date: Option<String>,
-    pub cover_art: Option<Vec<u8>>,
     pub path: PathBuf,
+    #[serde(default)]
+    pub replay_gain: Option<ReplayGain>,  // 未読み込み（古いプレイリスト）の場合はNone
 }
 
 impl TrackInfo {
@@ -25,22 +28,21 @@ impl TrackInfo {
     }
 }
 
-pub fn get_flac_metadata(path: &Path) -> Option<TrackInfo> {
+pub fn get_track_metadata(path: &Path) -> Option<TrackInfo> {
     // Step 4-2: ファイル存在確認とエラーハンドリング強化
     if !path.exists() {
         eprintln!("Warning: Audio file not found: {}", path.display());
         return None;
     }
 
-    if !is_flac_file(path) {
-        eprintln!("Warning: File is not a FLAC file: {}", path.display());
+    if !is_supported_audio_file(path) {
+        eprintln!("Warning: File is not a supported audio file: {}", path.display());
         return None;
     }
 
-    match metaflac::Tag::read_from_path(path) {
+    match read_tags(path) {
         Ok(tag) => {
-            let title = tag.get_vorbis("TITLE")
-                .and_then(|mut iter| iter.next())
+            let title = tag.get("TITLE")
                 .map(|s| s.to_string())
                 .unwrap_or_else(|| {
                     path.file_stem()
@@ -49,74 +51,53 @@ pub fn get_flac_metadata(path: &Path) -> Option<TrackInfo> {
                         .to_string()
                 });
             
-            let album_artist = tag.get_vorbis("ALBUMARTIST")
-                .and_then(|mut iter| iter.next())
+            let album_artist = tag.get("ALBUMARTIST")
                 .map(|s| s.to_string());
             
-            let artist = tag.get_vorbis("ARTIST")
-                .and_then(|mut iter| iter.next())
+            let artist = tag.get("ARTIST")
                 .map(|s| s.to_string())
-                .unwrap_or_else(|| String::new());
+                .unwrap_or_default();
             
-            let album = tag.get_vorbis("ALBUM")
-                .and_then(|mut iter| iter.next())
+            let album = tag.get("ALBUM")
                 .map(|s| s.to_string())
                 .unwrap_or_else(|| "Unknown Album".to_string());
             
-            let track_number = tag.get_vorbis("TRACKNUMBER")
-                .and_then(|mut iter| iter.next())
+            let track_number = tag.get("TRACKNUMBER")
                 .and_then(|s| s.split('/').next())
-                .and_then(|s| s.parse::<u32>().ok());
+                .and_then(|s| s.trim().parse::<u32>().ok());
             
-            let track_total = tag.get_vorbis("TRACKTOTAL")
-                .and_then(|mut iter| iter.next())
-                .and_then(|s| s.parse::<u32>().ok())
+            let track_total = tag.get("TRACKTOTAL")
+                .and_then(|s| s.trim().parse::<u32>().ok())
                 .or_else(|| {
                     // TRACKNUMBER フィールドに "X/Y" 形式が含まれている場合の Y を取得
-                    tag.get_vorbis("TRACKNUMBER")
-                        .and_then(|mut iter| iter.next())
+                    tag.get("TRACKNUMBER")
                         .and_then(|s| s.split('/').nth(1))
-                        .and_then(|s| s.parse::<u32>().ok())
+                        .and_then(|s| s.trim().parse::<u32>().ok())
                 });
             
-            let disc_number = tag.get_vorbis("DISCNUMBER")
-                .and_then(|mut iter| iter.next())
+            let disc_number = tag.get("DISCNUMBER")
                 .and_then(|s| s.split('/').next())
-                .and_then(|s| s.parse::<u32>().ok());
+                .and_then(|s| s.trim().parse::<u32>().ok());
             
-            let disc_total = tag.get_vorbis("DISCTOTAL")
-                .and_then(|mut iter| iter.next())
-                .and_then(|s| s.parse::<u32>().ok())
+            let disc_total = tag.get("DISCTOTAL")
+                .and_then(|s| s.trim().parse::<u32>().ok())
                 .or_else(|| {
                     // DISCNUMBER フィールドに "X/Y" 形式が含まれている場合の Y を取得
-                    tag.get_vorbis("DISCNUMBER")
-                        .and_then(|mut iter| iter.next())
+                    tag.get("DISCNUMBER")
                         .and_then(|s| s.split('/').nth(1))
-                        .and_then(|s| s.parse::<u32>().ok())
+                        .and_then(|s| s.trim().parse::<u32>().ok())
                 });
             
-            let date = tag.get_vorbis("DATE")
-                .and_then(|mut iter| iter.next())
+            let date = tag.get("DATE")
                 .map(|s| s.to_string());
             
-            let composer = tag.get_vorbis("COMPOSER")
-                .and_then(|mut iter| iter.next())
+            let composer = tag.get("COMPOSER")
                 .map(|s| s.to_string());
             
-            let genre = tag.get_vorbis("GENRE")
-                .and_then(|mut iter| iter.next())
+            let genre = tag.get("GENRE")
                 .map(|s| s.to_string());
             
-            // カバーアート取得
-            let pictures: Vec<_> = tag.pictures().collect();
-            let cover_art = pictures.iter()
-                // 1. CoverFrontを最優先
-                .find(|pic| pic.picture_type == metaflac::block::PictureType::CoverFront)
-                // 2. Otherタイプも考慮（一部のエンコーダーが使用）
-                .or_else(|| pictures.iter().find(|pic| pic.picture_type == metaflac::block::PictureType::Other))
-                // 3. 最初の画像を使用
-                .or_else(|| pictures.first())
-                .map(|pic| pic.data.clone());
+            let replay_gain = ReplayGain::from_tags(&tag);
             
             Some(TrackInfo {
                 title,
@@ -130,21 +111,57 @@ pub fn get_flac_metadata(path: &Path) -> Option<TrackInfo> {
                 disc_number,
                 disc_total,
                 date,
-                cover_art,
                 path: path.to_path_buf(),
+                replay_gain: Some(replay_gain),
             })
         },
         Err(e) => {
-            eprintln!("Warning: Failed to read FLAC metadata from '{}': {}", path.display(), e);
+            eprintln!("Warning: {}", e);
             None
         }
     }
 }
 
-pub fn is_flac_file(path: &Path) -> bool {
-    if let Some(extension) = path.extension() {
-        extension.to_string_lossy().to_lowercase() == "flac"
-    } else {
-        false
+/// 埋め込み歌詞として読み取るタグ（Vorbisコメント名、優先順）
+const EMBEDDED_LYRICS_FIELDS: [&str; 3] = ["LYRICS", "UNSYNCEDLYRICS", "SYNCEDLYRICS"];
+
+/// 音声ファイルに埋め込まれた歌詞
+///
+/// プレイリストJSONを肥大化させないため TrackInfo には含めず、歌詞表示時に遅延読み込みする。
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmbeddedLyrics {
+    pub field_name: String, // 読み取ったタグ名
+    pub text: String,
+}
+
+/// タグから埋め込み歌詞を取得（空でないものを優先順に返す）
+pub fn get_embedded_lyrics(path: &Path) -> Vec<EmbeddedLyrics> {
+    if !is_supported_audio_file(path) {
+        return Vec::new();
     }
-}
\ No newline at end of file
+
+    let tag = match read_tags(path) {
+        Ok(tag) => tag,
+        Err(e) => {
+            eprintln!("Warning: {}", e);
+            return Vec::new();
+        }
+    };
+
+    EMBEDDED_LYRICS_FIELDS.iter()
+        .filter_map(|field_name| {
+            // 同じフィールドが複数ある場合は改行で連結
+            let text = tag.get_all(field_name)
+                .collect::<Vec<_>>()
+                .join("\n");
+            if text.trim().is_empty() {
+                None
+            } else {
+                Some(EmbeddedLyrics {
+                    field_name: field_name.to_string(),
+                    text,
+                })
+            }
+        })
+        .collect()
+}
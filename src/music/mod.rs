@@ -1,7 +1,16 @@
+pub mod cover_art;
+pub mod format;
 pub mod library;
+pub mod library_index;
+pub mod library_roots;
+pub mod library_scanner;
+pub mod library_watcher;
+pub mod loudness;
 pub mod metadata;
+pub mod replaygain;
+pub mod replaygain_scanner;
 pub mod tree;
 
 pub use library::MusicLibrary;
-pub use metadata::TrackInfo;
+pub use metadata::{TrackInfo, EmbeddedLyrics};
 pub use tree::{MusicTreeNode, MusicNodeType};
\ No newline at end of file
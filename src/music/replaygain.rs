@@ -0,0 +1,114 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use crate::settings::ReplayGainMode;
+use super::format::AudioTags;
+
+/// ReplayGain 2.0 の基準ラウドネス（LUFS）
+const REFERENCE_LOUDNESS_LUFS: f64 = -18.0;
+/// R128ゲイン（-23 LUFS基準）をReplayGain（-18 LUFS基準）に換算する差分（dB）
+const R128_TO_REPLAYGAIN_DB: f64 = 5.0;
+
+/// タグから読み取ったラウドネス正規化情報
+///
+/// REPLAYGAIN_* を優先し、無い場合は R128_*（Q7.8形式の整数）から換算する。
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct ReplayGain {
+    pub track_gain_db: Option<f64>,
+    pub track_peak: Option<f64>,
+    pub album_gain_db: Option<f64>,
+    pub album_peak: Option<f64>,
+}
+
+impl ReplayGain {
+    pub fn from_tags(tags: &AudioTags) -> Self {
+        Self {
+            track_gain_db: tags.get("REPLAYGAIN_TRACK_GAIN").and_then(parse_gain)
+                .or_else(|| tags.get("R128_TRACK_GAIN").and_then(parse_r128_gain)),
+            track_peak: tags.get("REPLAYGAIN_TRACK_PEAK").and_then(parse_peak),
+            album_gain_db: tags.get("REPLAYGAIN_ALBUM_GAIN").and_then(parse_gain)
+                .or_else(|| tags.get("R128_ALBUM_GAIN").and_then(parse_r128_gain)),
+            album_peak: tags.get("REPLAYGAIN_ALBUM_PEAK").and_then(parse_peak),
+        }
+    }
+
+    /// ラウドネス測定結果から計算（測定できなかった値はNone）
+    pub fn from_loudness(track_lufs: Option<f64>, track_peak: f64, album_lufs: Option<f64>, album_peak: f64) -> Self {
+        Self {
+            track_gain_db: track_lufs.map(|lufs| REFERENCE_LOUDNESS_LUFS - lufs),
+            track_peak: track_lufs.map(|_| track_peak),
+            album_gain_db: album_lufs.map(|lufs| REFERENCE_LOUDNESS_LUFS - lufs),
+            album_peak: album_lufs.map(|_| album_peak),
+        }
+    }
+
+    /// 再生時に適用するゲイン（dB）
+    ///
+    /// 選択したモードの値が無い場合はもう一方の値を使う。ゲイン情報が無い楽曲には
+    /// プリアンプも適用しない。クリッピング防止が有効な場合はピークが1.0を超えないよう制限する。
+    pub fn playback_gain_db(&self, mode: ReplayGainMode, preamp_db: f64, prevent_clipping: bool) -> f64 {
+        let selected = match mode {
+            ReplayGainMode::Off => return 0.0,
+            ReplayGainMode::Track => self.track_gain_db
+                .map(|gain| (gain, self.track_peak))
+                .or_else(|| self.album_gain_db.map(|gain| (gain, self.album_peak))),
+            ReplayGainMode::Album => self.album_gain_db
+                .map(|gain| (gain, self.album_peak))
+                .or_else(|| self.track_gain_db.map(|gain| (gain, self.track_peak))),
+        };
+        let Some((gain, peak)) = selected else {
+            return 0.0;
+        };
+
+        let gain = gain + preamp_db;
+        match peak {
+            Some(peak) if prevent_clipping && peak > 0.0 => gain.min(-20.0 * peak.log10()),
+            _ => gain,
+        }
+    }
+}
+
+/// "-6.54 dB" 形式のゲイン
+fn parse_gain(value: &str) -> Option<f64> {
+    let number = value.trim().trim_end_matches(|c: char| c.is_ascii_alphabetic()).trim();
+    number.parse::<f64>().ok().filter(|gain| gain.is_finite())
+}
+
+fn parse_peak(value: &str) -> Option<f64> {
+    value.trim().parse::<f64>().ok().filter(|peak| peak.is_finite() && *peak >= 0.0)
+}
+
+/// R128_*_GAIN は1/256 dB単位の整数
+fn parse_r128_gain(value: &str) -> Option<f64> {
+    let q78 = value.trim().parse::<i32>().ok()?;
+    Some(q78 as f64 / 256.0 + R128_TO_REPLAYGAIN_DB)
+}
+
+/// FLACファイルにREPLAYGAIN_*のVorbisコメントとして書き込む（値の無い項目は変更しない）
+pub fn write_flac_tags(path: &Path, replay_gain: &ReplayGain) -> Result<(), String> {
+    let mut tag = metaflac::Tag::read_from_path(path)
+        .map_err(|e| format!("Failed to read FLAC metadata from '{}': {}", path.display(), e))?;
+
+    let comments = tag.vorbis_comments_mut();
+    let fields = [
+        ("REPLAYGAIN_TRACK_GAIN", replay_gain.track_gain_db.map(|gain| format!("{:.2} dB", gain))),
+        ("REPLAYGAIN_TRACK_PEAK", replay_gain.track_peak.map(|peak| format!("{:.6}", peak))),
+        ("REPLAYGAIN_ALBUM_GAIN", replay_gain.album_gain_db.map(|gain| format!("{:.2} dB", gain))),
+        ("REPLAYGAIN_ALBUM_PEAK", replay_gain.album_peak.map(|peak| format!("{:.6}", peak))),
+    ];
+    for (name, value) in fields {
+        if let Some(value) = value {
+            // 小文字などで書かれた既存のフィールドが重複しないよう削除してから設定
+            let existing: Vec<String> = comments.comments.keys()
+                .filter(|key| key.eq_ignore_ascii_case(name))
+                .cloned()
+                .collect();
+            for key in existing {
+                comments.remove(&key);
+            }
+            comments.set(name, vec![value]);
+        }
+    }
+
+    tag.save()
+        .map_err(|e| format!("Failed to write FLAC metadata to '{}': {}", path.display(), e))
+}
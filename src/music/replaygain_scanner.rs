@@ -0,0 +1,183 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::thread;
+use super::format::AudioFormat;
+use super::library::album_group_key;
+use super::loudness::{album_loudness, analyze_file, TrackLoudness};
+use super::metadata::TrackInfo;
+use super::replaygain::{write_flac_tags, ReplayGain};
+
+/// 解析スレッドからUIへの通知
+enum ScanEvent {
+    TrackStarted(PathBuf),
+    TrackFinished,
+    TrackFailed(PathBuf, String),
+    AlbumCompleted(Vec<(PathBuf, ReplayGain)>),
+    Finished,
+}
+
+/// 解析の進捗
+#[derive(Debug, Clone, Default)]
+pub struct ScanProgress {
+    pub total: usize,
+    pub completed: usize,
+    pub current: Option<PathBuf>,
+    pub analyzed: usize,                   // ゲインを計算できた楽曲数
+    pub failures: Vec<(PathBuf, String)>,
+    pub cancelled: bool,
+    pub finished: bool,
+}
+
+/// ReplayGainタグの無いFLACをバックグラウンドで解析するジョブ
+pub struct ReplayGainScanner {
+    receiver: Option<Receiver<ScanEvent>>,
+    cancel: Arc<AtomicBool>,
+    progress: ScanProgress,
+}
+
+impl ReplayGainScanner {
+    pub fn new() -> Self {
+        Self {
+            receiver: None,
+            cancel: Arc::new(AtomicBool::new(false)),
+            progress: ScanProgress::default(),
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.receiver.is_some()
+    }
+
+    pub fn get_progress(&self) -> &ScanProgress {
+        &self.progress
+    }
+
+    /// アルバム単位で解析を開始（write_tagsがtrueの場合は結果をVorbisコメントに書き込む）
+    pub fn start(&mut self, albums: Vec<Vec<PathBuf>>, write_tags: bool) {
+        if self.is_running() {
+            return;
+        }
+
+        let (sender, receiver) = mpsc::channel();
+        self.cancel = Arc::new(AtomicBool::new(false));
+        self.progress = ScanProgress {
+            total: albums.iter().map(|album| album.len()).sum(),
+            ..Default::default()
+        };
+        self.receiver = Some(receiver);
+
+        let cancel = Arc::clone(&self.cancel);
+        thread::spawn(move || scan_albums(albums, write_tags, &cancel, &sender));
+    }
+
+    pub fn cancel(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+        self.progress.cancelled = true;
+    }
+
+    /// 解析スレッドからの通知を反映し、完了したアルバムの楽曲のゲインを返す
+    pub fn poll(&mut self) -> HashMap<PathBuf, ReplayGain> {
+        let mut gains = HashMap::new();
+        let Some(receiver) = &self.receiver else {
+            return gains;
+        };
+
+        let mut finished = false;
+        loop {
+            let event = match receiver.try_recv() {
+                Ok(event) => event,
+                Err(mpsc::TryRecvError::Empty) => break,
+                // 送信側が終了した場合（中断・パニック時を含む）も完了とする
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    finished = true;
+                    break;
+                }
+            };
+            match event {
+                ScanEvent::TrackStarted(path) => self.progress.current = Some(path),
+                ScanEvent::TrackFinished => self.progress.completed += 1,
+                ScanEvent::TrackFailed(path, error) => {
+                    eprintln!("Warning: Loudness analysis failed: {}", error);
+                    self.progress.failures.push((path, error));
+                }
+                ScanEvent::AlbumCompleted(album_gains) => {
+                    self.progress.analyzed += album_gains.len();
+                    gains.extend(album_gains);
+                }
+                ScanEvent::Finished => finished = true,
+            }
+        }
+
+        if finished {
+            self.receiver = None;
+            self.progress.current = None;
+            self.progress.finished = true;
+        }
+        gains
+    }
+}
+
+/// ReplayGainの無いFLACを含むアルバムを、アルバム内の全FLACとともに列挙する
+///
+/// アルバムゲインはアルバム全体から計算するため、タグ付け済みの楽曲も解析対象に含める。
+pub fn albums_needing_scan(tracks: &[TrackInfo]) -> Vec<Vec<PathBuf>> {
+    let mut albums: HashMap<(String, String), Vec<&TrackInfo>> = HashMap::new();
+    for track in tracks.iter().filter(|track| AudioFormat::from_path(&track.path) == Some(AudioFormat::Flac)) {
+        albums.entry(album_group_key(track)).or_default().push(track);
+    }
+
+    let mut albums: Vec<_> = albums.into_iter()
+        .filter(|(_, album_tracks)| {
+            album_tracks.iter().any(|track| {
+                track.replay_gain.is_none_or(|replay_gain| replay_gain.track_gain_db.is_none())
+            })
+        })
+        .collect();
+    albums.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    albums.into_iter()
+        .map(|(_, album_tracks)| album_tracks.into_iter().map(|track| track.path.clone()).collect())
+        .collect()
+}
+
+fn scan_albums(albums: Vec<Vec<PathBuf>>, write_tags: bool, cancel: &AtomicBool, sender: &Sender<ScanEvent>) {
+    for album in albums {
+        let mut analyses: Vec<(PathBuf, TrackLoudness)> = Vec::new();
+        for path in album {
+            if cancel.load(Ordering::Relaxed) {
+                return;
+            }
+
+            let _ = sender.send(ScanEvent::TrackStarted(path.clone()));
+            match analyze_file(&path, cancel) {
+                Ok(loudness) => analyses.push((path, loudness)),
+                Err(_) if cancel.load(Ordering::Relaxed) => return,
+                Err(error) => {
+                    let _ = sender.send(ScanEvent::TrackFailed(path, error));
+                }
+            }
+            let _ = sender.send(ScanEvent::TrackFinished);
+        }
+
+        let album_tracks: Vec<&TrackLoudness> = analyses.iter().map(|(_, loudness)| loudness).collect();
+        let (album_lufs, album_peak) = album_loudness(&album_tracks);
+
+        let mut album_gains = Vec::new();
+        for (path, loudness) in &analyses {
+            let replay_gain = ReplayGain::from_loudness(loudness.integrated_lufs(), loudness.true_peak, album_lufs, album_peak);
+            if write_tags {
+                if let Err(error) = write_flac_tags(path, &replay_gain) {
+                    let _ = sender.send(ScanEvent::TrackFailed(path.clone(), error));
+                    continue;
+                }
+            }
+            album_gains.push((path.clone(), replay_gain));
+        }
+        let _ = sender.send(ScanEvent::AlbumCompleted(album_gains));
+    }
+
+    let _ = sender.send(ScanEvent::Finished);
+}
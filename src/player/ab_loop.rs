@@ -0,0 +1,66 @@
+use std::time::Duration;
+
+/// ループ区間として扱う最短の長さ（これより短い区間はループしない）
+const MIN_LOOP_LENGTH: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AbLoopPoint {
+    A,
+    B,
+}
+
+/// A–Bリピートの開始点・終了点
+///
+/// 片方だけ設定された状態も保持し、両方が揃った時点で区間として有効になる。
+/// 逆順に指定された場合はAが前になるよう入れ替える。
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct AbLoop {
+    point_a: Option<Duration>,
+    point_b: Option<Duration>,
+}
+
+impl AbLoop {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_point(&mut self, point: AbLoopPoint, position: Duration) {
+        match point {
+            AbLoopPoint::A => self.point_a = Some(position),
+            AbLoopPoint::B => self.point_b = Some(position),
+        }
+        if let (Some(a), Some(b)) = (self.point_a, self.point_b) {
+            if b < a {
+                self.point_a = Some(b);
+                self.point_b = Some(a);
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn point_a(&self) -> Option<Duration> {
+        self.point_a
+    }
+
+    pub fn point_b(&self) -> Option<Duration> {
+        self.point_b
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.point_a.is_none() && self.point_b.is_none()
+    }
+
+    /// ループ再生する区間（A・Bの両方が設定され、十分な長さがある場合のみ）
+    pub fn region(&self) -> Option<(Duration, Duration)> {
+        let (a, b) = (self.point_a?, self.point_b?);
+        (b.saturating_sub(a) >= MIN_LOOP_LENGTH).then_some((a, b))
+    }
+
+    /// 位置が有効な区間の内側かどうか（終了点は含まない）
+    pub fn contains(&self, position: Duration) -> bool {
+        self.region().is_some_and(|(a, b)| a <= position && position < b)
+    }
+}
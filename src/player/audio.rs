@@ -1,11 +1,25 @@
 use crate::music::TrackInfo;
-use kira::manager::{AudioManager, AudioManagerSettings, backend::cpal::CpalBackend};
+use crate::music::replaygain::ReplayGain;
+use crate::settings::{CrossfadeCurve, ReplayGainMode};
+use kira::clock::ClockHandle;
 use kira::sound::streaming::{StreamingSoundData, StreamingSoundHandle};
-use kira::sound::{PlaybackState as KiraPlaybackState, FromFileError};
-use kira::clock::{ClockHandle, ClockSpeed};
-use kira::tween::Tween;
-use metaflac::Tag;
+use kira::sound::{PlaybackState as KiraPlaybackState, FromFileError, Region};
+use kira::{StartTime, Volume};
+use kira::tween::{Easing, Tween};
+use std::cell::Cell;
 use std::time::{Duration, Instant};
+use super::ab_loop::{AbLoop, AbLoopPoint};
+use super::engine::{create_engine, create_headless_engine, EngineManager, OutputEngine, SCHEDULE_CLOCK_TICKS_PER_SECOND};
+use super::pitch_shift::PitchShifterHandle;
+use super::position::PositionTracker;
+
+/// 手動で楽曲を切り替えた時のフェード時間
+const SKIP_FADE_DURATION: Duration = Duration::from_millis(300);
+/// 音量・ミュート変更時のフェード時間（ノイズ防止）
+const VOLUME_TWEEN_DURATION: Duration = Duration::from_millis(80);
+/// 再生速度の範囲
+pub const MIN_PLAYBACK_RATE: f64 = 0.25;
+pub const MAX_PLAYBACK_RATE: f64 = 2.0;
 
 #[derive(PartialEq, Clone, Debug)]
 pub enum PlaybackState {
@@ -15,64 +29,377 @@ pub enum PlaybackState {
 }
 
 pub struct AudioPlayer {
-    manager: Option<AudioManager<CpalBackend>>,
+    manager: Option<EngineManager>,          // 出力デバイスを開けなかった場合はNone
+    output_device: Option<String>,          // 使用中の出力デバイス名（Noneは既定のデバイス）
+    output_error: Option<String>,           // 出力デバイスに関するエラー（UIに表示）
     current_sound: Option<StreamingSoundHandle<FromFileError>>,
     current_track: Option<TrackInfo>,
     total_duration: Option<Duration>,
     state: PlaybackState,
-    #[allow(dead_code)]
-    clock: Option<ClockHandle>,
-    play_start_time: Option<Instant>,
-    paused_duration: Duration,
+    position_tracker: Cell<PositionTracker>, // エンジンの再生位置からの推定（&selfで更新するためCell）
+    schedule_clock: Option<ClockHandle>,     // ギャップレス再生・クロスフェードの開始時刻指定用
+    scheduled_next: Option<ScheduledTrack>,
+    fading_out_sound: Option<StreamingSoundHandle<FromFileError>>, // 手動切り替えでフェードアウト中の楽曲
+    crossfade_duration: Duration,            // 0の場合はギャップレスで切り替え
+    crossfade_easing: Easing,
+    replay_gain_mode: ReplayGainMode,
+    replay_gain_preamp_db: f64,
+    replay_gain_prevent_clipping: bool,
+    volume: f32,                             // 0.0〜1.0（スライダー上の値）
+    muted: bool,
+    playback_rate: f64,                      // 1.0で等速（予約済みの次の楽曲にも適用）
+    preserve_pitch: bool,                    // 速度変更時に音程を保つ
+    pitch_shifter: Option<PitchShifterHandle>,
+    ab_loop: AbLoop,                         // 現在の楽曲のA–Bリピート区間
+}
+
+/// 現在の楽曲の終了に合わせて開始するよう予約した次の楽曲
+struct ScheduledTrack {
+    sound: StreamingSoundHandle<FromFileError>,
+    track: TrackInfo,
+    duration: Option<Duration>,
+    crossfade: bool,  // 現在の楽曲にフェードアウトを予約したかどうか
 }
 
 impl AudioPlayer {
-    pub fn new() -> Self {
-        let mut manager = AudioManager::<CpalBackend>::new(AudioManagerSettings::default()).ok();
-        let clock = manager.as_mut().and_then(|m| m.add_clock(ClockSpeed::TicksPerSecond(44100.0)).ok());
+    /// 指定した出力デバイス（Noneは既定のデバイス）で初期化
+    ///
+    /// 指定したデバイスが見つからない場合は既定のデバイスを使い、その旨をエラーとして保持する。
+    pub fn new(output_device: Option<&str>) -> Self {
+        let (engine, output_error) = match create_engine(output_device) {
+            Ok(engine) => (Some(engine), None),
+            Err(error) if output_device.is_some() => match create_engine(None) {
+                Ok(engine) => (Some(engine), Some(format!("{}（既定のデバイスを使用します）", error))),
+                Err(default_error) => (None, Some(default_error)),
+            },
+            Err(error) => (None, Some(error)),
+        };
+        if let Some(error) = &output_error {
+            eprintln!("Warning: Audio output: {}", error);
+        }
+
+        // 既定のデバイスで代替した場合は使用中のデバイスを既定として扱う
+        let used_device = output_device.filter(|_| output_error.is_none()).map(str::to_string);
+        Self::with_engine(engine, used_device, output_error)
+    }
+
+    /// 出力デバイスを使わない仮想出力で初期化（テスト用）
+    ///
+    /// 時間はadvance_headlessを呼んだ分だけ進むため、再生位置や楽曲の終了を決定的に扱える。
+    #[allow(dead_code)]
+    pub fn new_headless() -> Self {
+        match create_headless_engine() {
+            Ok(engine) => Self::with_engine(Some(engine), None, None),
+            Err(error) => Self::with_engine(None, None, Some(error)),
+        }
+    }
+
+    fn with_engine(engine: Option<OutputEngine>, output_device: Option<String>, output_error: Option<String>) -> Self {
+        let (manager, schedule_clock, pitch_shifter) = match engine {
+            Some(engine) => (Some(engine.manager), engine.schedule_clock, Some(engine.pitch_shifter)),
+            None => (None, None, None),
+        };
         
         Self {
             manager,
+            output_device,
+            output_error,
             current_sound: None,
             current_track: None,
             total_duration: None,
             state: PlaybackState::Stopped,
-            clock,
-            play_start_time: None,
-            paused_duration: Duration::from_secs(0),
+            position_tracker: Cell::new(PositionTracker::new()),
+            schedule_clock,
+            scheduled_next: None,
+            fading_out_sound: None,
+            crossfade_duration: Duration::ZERO,
+            crossfade_easing: Easing::Linear,
+            replay_gain_mode: ReplayGainMode::Off,
+            replay_gain_preamp_db: 0.0,
+            replay_gain_prevent_clipping: true,
+            volume: 1.0,
+            muted: false,
+            playback_rate: 1.0,
+            preserve_pitch: true,
+            pitch_shifter,
+            ab_loop: AbLoop::new(),
         }
     }
 
-    pub fn play(&mut self, track: TrackInfo) -> Result<(), Box<dyn std::error::Error>> {
+    pub fn get_output_device(&self) -> Option<&str> {
+        self.output_device.as_deref()
+    }
+
+    pub fn get_output_error(&self) -> Option<&str> {
+        self.output_error.as_deref()
+    }
+
+    /// 出力デバイスを切り替え（再生中・一時停止中の楽曲は同じ位置から続ける）
+    ///
+    /// 新しいデバイスを開けなかった場合は現在のデバイスのまま再生を続ける。
+    pub fn set_output_device(&mut self, output_device: Option<String>) -> Result<(), String> {
+        if output_device == self.output_device && self.manager.is_some() {
+            return Ok(());
+        }
+
+        let engine = match create_engine(output_device.as_deref()) {
+            Ok(engine) => engine,
+            Err(error) => {
+                self.output_error = Some(error.clone());
+                return Err(error);
+            }
+        };
+
+        // 切り替え前の再生状態を保存してから古いエンジンの楽曲を停止
+        let resume = self.current_track.clone()
+            .map(|track| (track, self.get_playback_position(), self.state.clone(), self.ab_loop));
         self.stop();
 
-        // 総再生時間をメタデータから取得（borrowingを避けるために先に実行）
-        let duration = Self::get_flac_duration_static(&track.path);
+        self.manager = Some(engine.manager);
+        self.schedule_clock = engine.schedule_clock;
+        self.pitch_shifter = Some(engine.pitch_shifter);
+        self.output_device = output_device;
+        self.output_error = None;
+        self.apply_output_volume();
+        self.apply_pitch_correction();
+
+        // 出力バッファ長はデバイス固有なので推定し直す
+        let mut position_tracker = PositionTracker::new();
+        position_tracker.set_playback_rate(self.playback_rate);
+        self.position_tracker.set(position_tracker);
+
+        if let Some((track, position, state, ab_loop)) = resume {
+            if let Err(e) = self.start_sound(track, None) {
+                let error = format!("出力デバイスの切り替え後に再生を再開できません: {}", e);
+                self.output_error = Some(error.clone());
+                return Err(error);
+            }
+            if !position.is_zero() {
+                self.seek_sound_to(position.as_secs_f64());
+            }
+            self.ab_loop = ab_loop;
+            self.apply_ab_loop();
+            if state == PlaybackState::Paused {
+                self.pause();
+            }
+        }
+        Ok(())
+    }
+
+    /// 再生速度を設定（再生中の楽曲にも即座に反映）
+    pub fn set_playback_rate(&mut self, playback_rate: f64) {
+        let playback_rate = if playback_rate.is_finite() {
+            playback_rate.clamp(MIN_PLAYBACK_RATE, MAX_PLAYBACK_RATE)
+        } else {
+            1.0
+        };
+        if playback_rate == self.playback_rate {
+            return;
+        }
+
+        // 終了時刻が変わるため予約済みの次の楽曲は取り消す（次のフレームで予約し直される）
+        self.cancel_scheduled_next();
+        self.playback_rate = playback_rate;
+        if let Some(ref mut sound) = self.current_sound {
+            sound.set_playback_rate(playback_rate, Tween::default());
+        }
+        self.position_tracker.get_mut().set_playback_rate(playback_rate);
+        self.apply_pitch_correction();
+    }
+
+    pub fn get_playback_rate(&self) -> f64 {
+        self.playback_rate
+    }
+
+    /// 速度変更時に音程を保つかどうか（無効の場合は速度に応じて音程も変わる）
+    pub fn set_preserve_pitch(&mut self, preserve_pitch: bool) {
+        self.preserve_pitch = preserve_pitch;
+        self.apply_pitch_correction();
+    }
+
+    pub fn is_preserve_pitch(&self) -> bool {
+        self.preserve_pitch
+    }
 
+    pub fn get_ab_loop(&self) -> &AbLoop {
+        &self.ab_loop
+    }
+
+    /// A–Bリピートの開始点・終了点を設定（両方揃った時点でループ再生を開始）
+    pub fn set_ab_loop_point(&mut self, point: AbLoopPoint, position: Duration) {
+        if self.current_sound.is_none() {
+            return;
+        }
+        let position = self.total_duration.map_or(position, |total| position.min(total));
+        self.ab_loop.set_point(point, position);
+        self.apply_ab_loop();
+    }
+
+    pub fn clear_ab_loop(&mut self) {
+        self.ab_loop.clear();
+        self.apply_ab_loop();
+    }
+
+    /// エンジンのループ区間に反映（区間の折り返しはエンジン側でサンプル単位で行う）
+    fn apply_ab_loop(&mut self) {
+        let region = self.ab_loop.region();
+        if region.is_some() {
+            // ループ中は楽曲が終了しないため次の楽曲の予約は取り消す
+            self.cancel_scheduled_next();
+        }
+        if let Some(ref mut sound) = self.current_sound {
+            sound.set_loop_region(region.map(|(a, b)| Region::from(a.as_secs_f64()..b.as_secs_f64())));
+        }
+
+        // 区間外を再生中の場合は開始点へ移動
+        if let Some((a, _)) = region {
+            if !self.ab_loop.contains(self.get_playback_position()) {
+                self.seek_sound_to(a.as_secs_f64());
+            }
+        }
+    }
+
+    fn apply_pitch_correction(&mut self) {
+        let ratio = if self.preserve_pitch { 1.0 / self.playback_rate } else { 1.0 };
+        if let Some(ref mut pitch_shifter) = self.pitch_shifter {
+            pitch_shifter.set_ratio(ratio);
+        }
+    }
+
+    /// 出力音量を設定（0.0〜1.0）
+    pub fn set_volume(&mut self, volume: f32) {
+        self.volume = if volume.is_nan() { 1.0 } else { volume.clamp(0.0, 1.0) };
+        self.apply_output_volume();
+    }
+
+    pub fn get_volume(&self) -> f32 {
+        self.volume
+    }
+
+    pub fn set_muted(&mut self, muted: bool) {
+        self.muted = muted;
+        self.apply_output_volume();
+    }
+
+    pub fn is_muted(&self) -> bool {
+        self.muted
+    }
+
+    pub fn toggle_mute(&mut self) {
+        self.set_muted(!self.muted);
+    }
+
+    /// メイントラックの音量に反映（楽曲ごとの音量はReplayGain用に残しておく）
+    fn apply_output_volume(&mut self) {
+        // スライダーの操作量と聴感上の音量変化が近くなるよう2乗カーブで振幅に変換
+        let amplitude = if self.muted { 0.0 } else { (self.volume as f64).powi(2) };
         if let Some(manager) = &mut self.manager {
-            let sound_data = StreamingSoundData::from_file(&track.path)?;
-            let sound_handle = manager.play(sound_data)?;
-            
-            self.current_sound = Some(sound_handle);
-            self.current_track = Some(track);
-            self.total_duration = duration;
-            self.state = PlaybackState::Playing;
-            self.play_start_time = Some(Instant::now());
-            self.paused_duration = Duration::from_secs(0);
+            manager.main_track().set_volume(Volume::Amplitude(amplitude), Tween {
+                duration: VOLUME_TWEEN_DURATION,
+                ..Default::default()
+            });
+        }
+    }
+
+    /// ReplayGainによる音量補正の設定（再生中・予約済みの楽曲にも反映）
+    pub fn set_replay_gain(&mut self, mode: ReplayGainMode, preamp_db: f64, prevent_clipping: bool) {
+        self.replay_gain_mode = mode;
+        self.replay_gain_preamp_db = preamp_db;
+        self.replay_gain_prevent_clipping = prevent_clipping;
+
+        if let Some(track) = &self.current_track {
+            let volume = self.track_volume(track);
+            if let Some(ref mut sound) = self.current_sound {
+                sound.set_volume(volume, Tween::default());
+            }
+        }
+        if let Some(next) = &self.scheduled_next {
+            let volume = self.track_volume(&next.track);
+            if let Some(ref mut next) = self.scheduled_next {
+                next.sound.set_volume(volume, Tween::default());
+            }
+        }
+    }
+
+    /// 楽曲の再生音量（ReplayGainの補正を適用）
+    fn track_volume(&self, track: &TrackInfo) -> Volume {
+        let gain_db = track.replay_gain.map_or(0.0, |replay_gain| {
+            replay_gain.playback_gain_db(self.replay_gain_mode, self.replay_gain_preamp_db, self.replay_gain_prevent_clipping)
+        });
+        Volume::Decibels(gain_db)
+    }
+
+    /// 曲間のクロスフェード設定（手動切り替え時のフェードにもカーブを適用）
+    pub fn set_crossfade(&mut self, duration: Duration, curve: CrossfadeCurve) {
+        self.crossfade_duration = duration;
+        self.crossfade_easing = match curve {
+            CrossfadeCurve::Linear => Easing::Linear,
+            CrossfadeCurve::SCurve => Easing::InOutPowi(2),
+        };
+    }
+
+    pub fn get_crossfade_duration(&self) -> Duration {
+        self.crossfade_duration
+    }
+
+    pub fn play(&mut self, track: TrackInfo) -> Result<(), Box<dyn std::error::Error>> {
+        self.stop();
+        self.start_sound(track, None)
+    }
+
+    /// 再生中の楽曲をフェードアウトさせながら新しい楽曲をフェードインで開始（手動スキップ用）
+    pub fn play_with_fade(&mut self, track: TrackInfo) -> Result<(), Box<dyn std::error::Error>> {
+        if self.state != PlaybackState::Playing {
+            return self.play(track);
+        }
+
+        let fade = Tween {
+            duration: SKIP_FADE_DURATION,
+            easing: self.crossfade_easing,
+            ..Default::default()
+        };
+        if let Some(mut sound) = self.current_sound.take() {
+            sound.stop(fade);
+            if let Some(mut previous) = self.fading_out_sound.replace(sound) {
+                previous.stop(Tween::default());
+            }
         }
+        self.stop_current();
+        self.start_sound(track, Some(fade))
+    }
+
+    fn start_sound(&mut self, track: TrackInfo, fade_in: Option<Tween>) -> Result<(), Box<dyn std::error::Error>> {
+        let track = with_replay_gain(track);
+        let volume = self.track_volume(&track);
 
+        let Some(manager) = &mut self.manager else {
+            return Err(self.output_error.clone()
+                .unwrap_or_else(|| "オーディオ出力デバイスがありません".to_string())
+                .into());
+        };
+        let sound_data = StreamingSoundData::from_file(&track.path)?
+            .volume(volume)
+            .playback_rate(self.playback_rate)
+            .fade_in_tween(fade_in);
+        // 総再生時間はデコーダーが読み込んだストリーム情報から取得（ファイルを読み直さない）
+        let duration = Some(sound_data.duration());
+        let sound_handle = manager.play(sound_data)?;
+        
+        self.current_sound = Some(sound_handle);
+        self.current_track = Some(track);
+        self.total_duration = duration;
+        self.state = PlaybackState::Playing;
+        self.reset_position_tracker();
         Ok(())
     }
 
     pub fn pause(&mut self) {
+        // 一時停止のフェードが予約済みのフェードアウトを上書きするため、現在の楽曲は戻さない
+        self.discard_scheduled_next();
+        self.stop_fading_out();
         if let Some(ref mut sound) = self.current_sound {
-            // 現在までの再生時間を記録
-            if let Some(start_time) = self.play_start_time {
-                self.paused_duration = self.paused_duration + start_time.elapsed();
-            }
             let _ = sound.pause(Tween::default());
             self.state = PlaybackState::Paused;
-            self.play_start_time = None;
         }
     }
 
@@ -80,11 +407,32 @@ impl AudioPlayer {
         if let Some(ref mut sound) = self.current_sound {
             let _ = sound.resume(Tween::default());
             self.state = PlaybackState::Playing;
-            self.play_start_time = Some(Instant::now());
         }
     }
 
+    /// 楽曲を指定位置で一時停止した状態で読み込む（前回終了時の再生状態の復元用）
+    pub fn load_paused(&mut self, track: TrackInfo, position: Duration) -> Result<(), Box<dyn std::error::Error>> {
+        self.stop();
+        // 一時停止が反映されるまでの間に音が出ないよう、フェードインで開始してすぐに止める
+        let fade_in = Tween {
+            duration: SKIP_FADE_DURATION,
+            ..Default::default()
+        };
+        self.start_sound(track, Some(fade_in))?;
+        self.pause();
+        if !position.is_zero() {
+            self.seek_to_position(position)?;
+        }
+        Ok(())
+    }
+
     pub fn stop(&mut self) {
+        self.stop_fading_out();
+        self.stop_current();
+    }
+
+    fn stop_current(&mut self) {
+        self.discard_scheduled_next();
         if let Some(ref mut sound) = self.current_sound {
             let _ = sound.stop(Tween::default());
         }
@@ -92,8 +440,8 @@ impl AudioPlayer {
         self.current_track = None;
         self.total_duration = None;
         self.state = PlaybackState::Stopped;
-        self.play_start_time = None;
-        self.paused_duration = Duration::from_secs(0);
+        self.ab_loop.clear();
+        self.reset_position_tracker();
     }
 
     #[allow(dead_code)]
@@ -111,27 +459,58 @@ impl AudioPlayer {
         &self.state
     }
 
-    #[allow(dead_code)]
     pub fn get_current_track(&self) -> Option<&TrackInfo> {
         self.current_track.as_ref()
     }
 
+    /// 再生位置（オーディオエンジンの位置を唯一の基準とし、出力遅延を補正）
     pub fn get_playback_position(&self) -> Duration {
-        match self.state {
-            PlaybackState::Playing => {
-                if let Some(start_time) = self.play_start_time {
-                    self.paused_duration + start_time.elapsed()
-                } else {
-                    self.paused_duration
-                }
-            },
-            PlaybackState::Paused => {
-                self.paused_duration
-            },
-            PlaybackState::Stopped => {
-                Duration::from_secs(0)
+        let sound = match (&self.state, &self.current_sound) {
+            (PlaybackState::Stopped, _) | (_, None) => return Duration::from_secs(0),
+            (_, Some(sound)) => sound,
+        };
+
+        let mut tracker = self.position_tracker.get();
+        let is_playing = self.state == PlaybackState::Playing;
+        let position = tracker.update(sound.position(), is_playing, self.now());
+        self.position_tracker.set(tracker);
+
+        Duration::from_secs_f64(position.max(0.0))
+    }
+
+    /// 仮想出力の時間を進める（new_headlessで作成した場合のみ有効）
+    #[allow(dead_code)]
+    pub fn advance_headless(&mut self, duration: Duration) {
+        if let Some(EngineManager::Headless(output)) = &mut self.manager {
+            output.advance(duration, self.current_sound.as_ref());
+        }
+    }
+
+    /// 再生位置の推定に使う現在時刻
+    fn now(&self) -> Instant {
+        self.manager.as_ref().map_or_else(Instant::now, EngineManager::now)
+    }
+
+    fn reset_position_tracker(&mut self) {
+        self.position_tracker.get_mut().reset();
+    }
+
+    /// シーク要求を送り、エンジンに反映されるまではシーク先を再生位置とする
+    fn seek_sound_to(&mut self, position_seconds: f64) {
+        // 終了時刻が変わるため予約済みの次の楽曲は取り消す
+        self.cancel_scheduled_next();
+        // A–Bリピートの区間外へのシークはループを解除する（エンジンが区間内に折り返さないように）
+        if self.ab_loop.region().is_some() && !self.ab_loop.contains(Duration::from_secs_f64(position_seconds.max(0.0))) {
+            self.ab_loop.clear();
+            if let Some(ref mut sound) = self.current_sound {
+                sound.set_loop_region(None);
             }
         }
+        let now = self.now();
+        if let Some(ref mut sound) = self.current_sound {
+            sound.seek_to(position_seconds);
+            self.position_tracker.get_mut().start_seek(position_seconds, now);
+        }
     }
 
     pub fn restart_current(&mut self) -> Result<(), Box<dyn std::error::Error>> {
@@ -153,9 +532,123 @@ impl AudioPlayer {
         self.total_duration
     }
 
+    /// 現在の楽曲の残り時間（実時間、総再生時間が不明な場合はNone）
+    pub fn get_remaining_time(&self) -> Option<Duration> {
+        let total = self.total_duration?;
+        Some(total.saturating_sub(self.get_playback_position()).div_f64(self.playback_rate))
+    }
+
+    /// 次の楽曲を先に開いておき、現在の楽曲の終了時刻ちょうどに開始するよう予約する
+    ///
+    /// 終了時刻はエンジン上の再生位置とクロックの同時刻の値から求めるため、
+    /// UIの更新タイミングに関係なくサンプル単位で連続して再生される。
+    /// クロスフェードが有効な場合は終了のクロスフェード時間前に開始し、
+    /// 現在の楽曲のフェードアウトと次の楽曲のフェードインを同じ時刻に予約する。
+    pub fn schedule_next(&mut self, track: TrackInfo) -> Result<(), Box<dyn std::error::Error>> {
+        self.cancel_scheduled_next();
+
+        if self.state != PlaybackState::Playing || self.position_tracker.get().is_seeking() || self.ab_loop.region().is_some() {
+            return Ok(());
+        }
+        let (Some(clock), Some(sound), Some(total)) =
+            (&self.schedule_clock, &self.current_sound, self.total_duration)
+        else {
+            return Ok(());
+        };
+
+        // 再生位置とクロックは出力バッファの開始時に順に更新されるため、
+        // 読み取り中に更新が挟まった場合は読み直す
+        let (position, clock_time) = loop {
+            let position = sound.position();
+            let clock_time = clock.time();
+            if sound.position() == position {
+                break (position, clock_time);
+            }
+        };
+        // 再生速度を変更している場合は楽曲上の残り時間を実時間に換算
+        let remaining_seconds = (total.as_secs_f64() - position).max(0.0) / self.playback_rate;
+        let fade_seconds = self.crossfade_duration.as_secs_f64().min(remaining_seconds);
+        let start_time = StartTime::ClockTime(
+            clock_time + (remaining_seconds - fade_seconds) * SCHEDULE_CLOCK_TICKS_PER_SECOND,
+        );
+        let fade = (fade_seconds > 0.0).then(|| Tween {
+            start_time,
+            duration: Duration::from_secs_f64(fade_seconds),
+            easing: self.crossfade_easing,
+        });
+
+        let track = with_replay_gain(track);
+        let volume = self.track_volume(&track);
+        let Some(manager) = &mut self.manager else {
+            return Ok(());
+        };
+        let sound_data = StreamingSoundData::from_file(&track.path)?
+            .volume(volume)
+            .playback_rate(self.playback_rate)
+            .start_time(start_time)
+            .fade_in_tween(fade);
+        let duration = Some(sound_data.duration());
+        let sound_handle = manager.play(sound_data)?;
+
+        if let (Some(fade), Some(sound)) = (fade, &mut self.current_sound) {
+            sound.stop(fade);
+        }
+        self.scheduled_next = Some(ScheduledTrack {
+            sound: sound_handle,
+            track,
+            duration,
+            crossfade: fade.is_some(),
+        });
+        Ok(())
+    }
+
+    /// 開始を予約済みの次の楽曲
+    pub fn get_scheduled_track(&self) -> Option<&TrackInfo> {
+        self.scheduled_next.as_ref().map(|next| &next.track)
+    }
+
+    /// 予約を取り消し、現在の楽曲に予約したフェードアウトも元に戻す
+    pub fn cancel_scheduled_next(&mut self) {
+        if let Some(crossfade) = self.discard_scheduled_next() {
+            if crossfade {
+                if let Some(ref mut sound) = self.current_sound {
+                    sound.resume(Tween::default());
+                }
+            }
+        }
+    }
+
+    /// 予約した楽曲を停止して破棄（フェードアウトを予約していたかどうかを返す）
+    fn discard_scheduled_next(&mut self) -> Option<bool> {
+        let mut next = self.scheduled_next.take()?;
+        next.sound.stop(Tween::default());
+        Some(next.crossfade)
+    }
+
+    fn stop_fading_out(&mut self) {
+        if let Some(mut sound) = self.fading_out_sound.take() {
+            sound.stop(Tween::default());
+        }
+    }
+
+    /// 予約済みの次の楽曲を現在の楽曲にする（現在の楽曲の終了後に呼ぶ）
+    pub fn advance_to_scheduled(&mut self) -> bool {
+        let Some(next) = self.scheduled_next.take() else {
+            return false;
+        };
+
+        self.current_sound = Some(next.sound);
+        self.current_track = Some(next.track);
+        self.total_duration = next.duration;
+        self.state = PlaybackState::Playing;
+        self.ab_loop.clear();
+        self.reset_position_tracker();
+        true
+    }
+
     pub fn seek_backward(&mut self, seconds: u32) -> Result<(), Box<dyn std::error::Error>> {
-        if let Some(ref mut sound) = self.current_sound {
-            let current_position = sound.position();
+        if self.current_sound.is_some() {
+            let current_position = self.get_playback_position().as_secs_f64();
             let seek_seconds = seconds as f64;
             
             // 新しい位置を計算（0秒未満にならないように）
@@ -165,20 +658,14 @@ impl AudioPlayer {
                 0.0
             };
             
-            sound.seek_to(new_position);
-            
-            // 内部状態を更新
-            self.paused_duration = Duration::from_secs_f64(new_position);
-            if matches!(self.state, PlaybackState::Playing) {
-                self.play_start_time = Some(Instant::now());
-            }
+            self.seek_sound_to(new_position);
         }
         Ok(())
     }
 
     pub fn seek_forward(&mut self, seconds: u32) -> Result<(), Box<dyn std::error::Error>> {
-        if let Some(ref mut sound) = self.current_sound {
-            let current_position = sound.position();
+        if self.current_sound.is_some() {
+            let current_position = self.get_playback_position().as_secs_f64();
             let seek_seconds = seconds as f64;
             
             // 新しい位置を計算（総時間を超えないように）
@@ -196,19 +683,13 @@ impl AudioPlayer {
                 current_position + seek_seconds
             };
             
-            sound.seek_to(new_position);
-            
-            // 内部状態を更新
-            self.paused_duration = Duration::from_secs_f64(new_position);
-            if matches!(self.state, PlaybackState::Playing) {
-                self.play_start_time = Some(Instant::now());
-            }
+            self.seek_sound_to(new_position);
         }
         Ok(())
     }
 
     pub fn seek_to_position(&mut self, position: Duration) -> Result<(), Box<dyn std::error::Error>> {
-        if let Some(ref mut sound) = self.current_sound {
+        if self.current_sound.is_some() {
             let target_position = position.as_secs_f64();
             
             // 総時間を超えないように制限
@@ -225,13 +706,7 @@ impl AudioPlayer {
                 target_position
             };
             
-            sound.seek_to(new_position);
-            
-            // 内部状態を更新
-            self.paused_duration = Duration::from_secs_f64(new_position);
-            if matches!(self.state, PlaybackState::Playing) {
-                self.play_start_time = Some(Instant::now());
-            }
+            self.seek_sound_to(new_position);
         }
         Ok(())
     }
@@ -242,21 +717,14 @@ impl AudioPlayer {
         self.stop();
         Ok(())
     }
+}
 
-    fn get_flac_duration_static(path: &std::path::Path) -> Option<Duration> {
-        if path.extension()?.to_str()? == "flac" {
-            let tag = Tag::read_from_path(path).ok()?;
-            let streaminfo = tag.get_streaminfo()?;
-            let total_samples = streaminfo.total_samples;
-            let sample_rate = streaminfo.sample_rate;
-            if total_samples > 0 && sample_rate > 0 {
-                Some(Duration::from_secs_f64(total_samples as f64 / sample_rate as f64))
-            } else {
-                None
-            }
-        } else {
-            // FLAC以外のファイルの場合はNone（後で他フォーマット対応可能）
-            None
-        }
+/// ゲイン情報を持たない楽曲（以前に保存したプレイリスト等）はファイルのタグから読み込む
+fn with_replay_gain(mut track: TrackInfo) -> TrackInfo {
+    if track.replay_gain.is_none() {
+        track.replay_gain = crate::music::format::read_text_tags(&track.path)
+            .ok()
+            .map(|tags| ReplayGain::from_tags(&tags));
     }
-}
\ No newline at end of file
+    track
+}
@@ -0,0 +1,170 @@
+use kira::clock::{ClockHandle, ClockSpeed};
+use kira::manager::backend::Backend;
+use kira::manager::backend::cpal::{CpalBackend, CpalBackendSettings};
+use kira::manager::backend::mock::{MockBackend, MockBackendSettings};
+use kira::manager::{AudioManager, AudioManagerSettings, error::PlaySoundError};
+use kira::sound::streaming::{StreamingSoundData, StreamingSoundHandle};
+use kira::sound::{FromFileError, PlaybackState as KiraPlaybackState};
+use kira::track::{TrackBuilder, TrackHandle};
+use std::time::{Duration, Instant};
+use super::output_device::find_output_device;
+use super::pitch_shift::{PitchShifterBuilder, PitchShifterHandle};
+
+/// 次の楽曲の開始時刻を指定するクロックの分解能（1サンプル未満の精度にする）
+pub(crate) const SCHEDULE_CLOCK_TICKS_PER_SECOND: f64 = 48_000.0;
+/// 仮想出力のサンプルレート
+const HEADLESS_SAMPLE_RATE: u32 = 48_000;
+/// 仮想出力が一度に処理するフレーム数（実際の出力デバイスのバッファに相当）
+const HEADLESS_BUFFER_FRAMES: u64 = 512;
+/// 楽曲データのデコードを待つ間隔と、1バッファあたりの最大待ち時間
+const HEADLESS_DECODE_WAIT: Duration = Duration::from_millis(1);
+const HEADLESS_DECODE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// オーディオエンジン（出力デバイス、またはテスト用の仮想出力）
+pub(crate) enum EngineManager {
+    Device(Box<AudioManager<CpalBackend>>),
+    Headless(Box<HeadlessOutput>),
+}
+
+impl EngineManager {
+    pub fn play(
+        &mut self,
+        sound_data: StreamingSoundData<FromFileError>,
+    ) -> Result<StreamingSoundHandle<FromFileError>, PlaySoundError<FromFileError>> {
+        match self {
+            EngineManager::Device(manager) => manager.play(sound_data),
+            EngineManager::Headless(output) => output.manager.play(sound_data),
+        }
+    }
+
+    pub fn main_track(&mut self) -> &mut TrackHandle {
+        match self {
+            EngineManager::Device(manager) => manager.main_track(),
+            EngineManager::Headless(output) => output.manager.main_track(),
+        }
+    }
+
+    /// 再生位置の補間に使う現在時刻（仮想出力では処理したフレーム数から求める）
+    pub fn now(&self) -> Instant {
+        match self {
+            EngineManager::Device(_) => Instant::now(),
+            EngineManager::Headless(output) => output.started_at + output.elapsed,
+        }
+    }
+}
+
+/// 音を出さず、呼び出し側が指定した分だけ時間を進める仮想出力
+pub(crate) struct HeadlessOutput {
+    manager: AudioManager<MockBackend>,
+    started_at: Instant,
+    elapsed: Duration,
+}
+
+impl HeadlessOutput {
+    /// 指定した時間分のフレームを処理する
+    ///
+    /// ストリーミング再生の楽曲データは別スレッドでデコードされるため、
+    /// 再生中の楽曲の位置が進まなかったバッファはデコードを待ってから処理し直す。
+    pub fn advance(&mut self, duration: Duration, sound: Option<&StreamingSoundHandle<FromFileError>>) {
+        let total_frames = (duration.as_secs_f64() * HEADLESS_SAMPLE_RATE as f64).round() as u64;
+        let mut processed_frames = 0;
+        let mut waited = Duration::ZERO;
+
+        self.manager.backend_mut().on_start_processing();
+        while processed_frames < total_frames {
+            let frames = HEADLESS_BUFFER_FRAMES.min(total_frames - processed_frames);
+            let position_before = sound.map(|sound| sound.position());
+
+            let backend = self.manager.backend_mut();
+            for _ in 0..frames {
+                let _ = backend.process();
+            }
+            backend.on_start_processing();
+
+            let stalled = sound.is_some_and(|sound| {
+                sound.state() == KiraPlaybackState::Playing && position_before == Some(sound.position())
+            });
+            if stalled && waited < HEADLESS_DECODE_TIMEOUT {
+                std::thread::sleep(HEADLESS_DECODE_WAIT);
+                waited += HEADLESS_DECODE_WAIT;
+                continue;
+            }
+
+            waited = Duration::ZERO;
+            processed_frames += frames;
+            self.elapsed += Duration::from_secs_f64(frames as f64 / HEADLESS_SAMPLE_RATE as f64);
+        }
+    }
+}
+
+/// 出力先ごとに作り直すオーディオエンジンと、エンジンに追加したクロック・エフェクト
+pub(crate) struct OutputEngine {
+    pub manager: EngineManager,
+    pub schedule_clock: Option<ClockHandle>,
+    pub pitch_shifter: PitchShifterHandle,
+}
+
+/// 出力デバイスを開いてオーディオエンジンを作成（Noneの場合は既定のデバイス）
+pub(crate) fn create_engine(output_device: Option<&str>) -> Result<OutputEngine, String> {
+    let device = match output_device {
+        Some(name) => Some(find_output_device(name)
+            .ok_or_else(|| format!("出力デバイス「{}」が見つかりません", name))?),
+        None => None,
+    };
+
+    let (manager, schedule_clock, pitch_shifter) = create_manager::<CpalBackend>(CpalBackendSettings {
+        device,
+        ..Default::default()
+    }).map_err(|e| match output_device {
+        Some(name) => format!("出力デバイス「{}」を開けません: {}", name, e),
+        None => format!("利用可能なオーディオ出力デバイスがありません: {}", e),
+    })?;
+
+    Ok(OutputEngine {
+        manager: EngineManager::Device(Box::new(manager)),
+        schedule_clock,
+        pitch_shifter,
+    })
+}
+
+/// 仮想出力でオーディオエンジンを作成
+pub(crate) fn create_headless_engine() -> Result<OutputEngine, String> {
+    let (manager, schedule_clock, pitch_shifter) = create_manager::<MockBackend>(MockBackendSettings {
+        sample_rate: HEADLESS_SAMPLE_RATE,
+    }).map_err(|_| "仮想出力を作成できません".to_string())?;
+
+    Ok(OutputEngine {
+        manager: EngineManager::Headless(Box::new(HeadlessOutput {
+            manager,
+            started_at: Instant::now(),
+            elapsed: Duration::ZERO,
+        })),
+        schedule_clock,
+        pitch_shifter,
+    })
+}
+
+fn create_manager<B: Backend>(
+    backend_settings: B::Settings,
+) -> Result<(AudioManager<B>, Option<ClockHandle>, PitchShifterHandle), B::Error>
+where
+    B::Settings: Default,
+{
+    // 再生速度の変更による音程の変化を打ち消すピッチシフターをメイントラックに挿入
+    let mut main_track_builder = TrackBuilder::new();
+    let pitch_shifter = main_track_builder.add_effect(PitchShifterBuilder);
+    let mut manager = AudioManager::<B>::new(AudioManagerSettings {
+        main_track_builder,
+        backend_settings,
+        ..Default::default()
+    })?;
+
+    let schedule_clock = manager.add_clock(ClockSpeed::TicksPerSecond(SCHEDULE_CLOCK_TICKS_PER_SECOND))
+        .ok()
+        .map(|mut clock| {
+            clock.start();
+            clock
+        });
+
+    Ok((manager, schedule_clock, pitch_shifter))
+}
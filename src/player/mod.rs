@@ -1,3 +1,11 @@
+pub mod ab_loop;
 pub mod audio;
+mod engine;
+pub mod output_device;
+pub mod pitch_shift;
+pub mod playback_rates;
+pub mod position;
 
+pub use ab_loop::{AbLoop, AbLoopPoint};
 pub use audio::{AudioPlayer, PlaybackState};
+pub use playback_rates::PlaybackRateManager;
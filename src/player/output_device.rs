@@ -0,0 +1,24 @@
+use cpal::traits::{DeviceTrait, HostTrait};
+
+/// 利用可能な出力デバイス名の一覧
+pub fn list_output_devices() -> Vec<String> {
+    let host = cpal::default_host();
+    let mut names: Vec<String> = match host.output_devices() {
+        Ok(devices) => devices.filter_map(|device| device.name().ok()).collect(),
+        Err(e) => {
+            eprintln!("Warning: Failed to enumerate audio output devices: {}", e);
+            Vec::new()
+        }
+    };
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// 名前が一致する出力デバイス
+pub fn find_output_device(name: &str) -> Option<cpal::Device> {
+    cpal::default_host()
+        .output_devices()
+        .ok()?
+        .find(|device| device.name().is_ok_and(|device_name| device_name == name))
+}
@@ -0,0 +1,115 @@
+use kira::clock::clock_info::ClockInfoProvider;
+use kira::effect::{Effect, EffectBuilder};
+use kira::modulator::value_provider::ModulatorValueProvider;
+use kira::Frame;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+
+/// 読み出し位置を循環させる遅延バッファの長さ（秒）
+const WINDOW_SECONDS: f64 = 0.05;
+
+/// 再生速度の変更で変わった音程を元に戻すためのピッチシフター
+///
+/// 遅延量を連続的に変化させた2つの読み出し位置を半周期ずらしてクロスフェードする
+/// （遅延線方式）。音程比が1.0の場合は入力をそのまま出力する。
+pub struct PitchShifterBuilder;
+
+impl EffectBuilder for PitchShifterBuilder {
+    type Handle = PitchShifterHandle;
+
+    fn build(self) -> (Box<dyn Effect>, Self::Handle) {
+        let ratio = Arc::new(AtomicU32::new(1.0f32.to_bits()));
+        let effect = PitchShifter {
+            ratio: Arc::clone(&ratio),
+            buffer: Vec::new(),
+            write_index: 0,
+            window: 0.0,
+            delay: 0.0,
+        };
+        (Box::new(effect), PitchShifterHandle { ratio })
+    }
+}
+
+/// オーディオスレッドのピッチシフターに音程比を伝えるハンドル
+pub struct PitchShifterHandle {
+    ratio: Arc<AtomicU32>,
+}
+
+impl PitchShifterHandle {
+    /// 音程比（2.0で1オクターブ上、1.0で無効）
+    pub fn set_ratio(&mut self, ratio: f64) {
+        self.ratio.store((ratio as f32).to_bits(), Ordering::Relaxed);
+    }
+}
+
+struct PitchShifter {
+    ratio: Arc<AtomicU32>,
+    buffer: Vec<Frame>,
+    write_index: usize,
+    window: f64,  // 遅延量の循環周期（サンプル数）
+    delay: f64,   // 1つ目の読み出し位置の遅延量（サンプル数、0..window）
+}
+
+impl PitchShifter {
+    fn allocate(&mut self, sample_rate: u32) {
+        self.window = (WINDOW_SECONDS * sample_rate as f64).round().max(4.0);
+        self.buffer = vec![Frame::ZERO; self.window as usize + 2];
+        self.write_index = 0;
+        self.delay = 0.0;
+    }
+
+    /// 現在の書き込み位置からdelayサンプル前の値（線形補間）
+    fn read(&self, delay: f64) -> Frame {
+        let len = self.buffer.len();
+        let whole = delay.floor();
+        let fraction = (delay - whole) as f32;
+        let newer = (self.write_index + len - whole as usize % len) % len;
+        let older = (newer + len - 1) % len;
+        self.buffer[newer] * (1.0 - fraction) + self.buffer[older] * fraction
+    }
+}
+
+impl Effect for PitchShifter {
+    fn init(&mut self, sample_rate: u32) {
+        self.allocate(sample_rate);
+    }
+
+    fn on_change_sample_rate(&mut self, sample_rate: u32) {
+        self.allocate(sample_rate);
+    }
+
+    fn process(
+        &mut self,
+        input: Frame,
+        _dt: f64,
+        _clock_info_provider: &ClockInfoProvider,
+        _modulator_value_provider: &ModulatorValueProvider,
+    ) -> Frame {
+        if self.buffer.is_empty() {
+            return input;
+        }
+
+        self.buffer[self.write_index] = input;
+        let ratio = f32::from_bits(self.ratio.load(Ordering::Relaxed)) as f64;
+
+        let output = if (ratio - 1.0).abs() < 1e-6 || !ratio.is_finite() || ratio <= 0.0 {
+            self.delay = 0.0;
+            input
+        } else {
+            // 遅延量が周期の端で不連続になる瞬間はsin²窓で音量を0にし、
+            // 半周期ずらしたもう一方の読み出し位置で補う（2つの窓の和は常に1）
+            let mut output = Frame::ZERO;
+            for offset in [0.0, self.window / 2.0] {
+                let delay = (self.delay + offset) % self.window;
+                let gain = (std::f64::consts::PI * delay / self.window).sin().powi(2) as f32;
+                output += self.read(delay) * gain;
+            }
+            // 遅延量を1サンプルあたり(1 - ratio)変化させると読み出し速度がratio倍になる
+            self.delay = (self.delay + 1.0 - ratio).rem_euclid(self.window);
+            output
+        };
+
+        self.write_index = (self.write_index + 1) % self.buffer.len();
+        output
+    }
+}
@@ -0,0 +1,87 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PlaybackRatesData {
+    pub version: String,
+    pub tracks: HashMap<PathBuf, f64>,
+}
+
+/// 楽曲ごとに記憶した再生速度（設定で有効にした場合のみ使用）
+pub struct PlaybackRateManager {
+    track_rates: HashMap<PathBuf, f64>, // メモリ常駐データ（1.0で等速）
+    rates_file: PathBuf,                // 単一JSONファイルパス
+}
+
+impl PlaybackRateManager {
+    pub fn new() -> Self {
+        Self::with_file_path(Self::get_rates_file_path())
+    }
+
+    pub fn with_file_path(rates_file: PathBuf) -> Self {
+        Self {
+            track_rates: HashMap::new(),
+            rates_file,
+        }
+    }
+
+    /// 記憶した再生速度（未設定の場合は等速）
+    pub fn get_rate(&self, track_path: &Path) -> f64 {
+        self.track_rates.get(track_path).copied().unwrap_or(1.0)
+    }
+
+    /// 再生速度を記憶（等速の場合は削除してファイルを肥大化させない）
+    pub fn set_rate(&mut self, track_path: &Path, rate: f64) {
+        if (rate - 1.0).abs() < 1e-9 {
+            self.track_rates.remove(track_path);
+        } else {
+            self.track_rates.insert(track_path.to_path_buf(), rate);
+        }
+    }
+
+    // 永続化（単一JSONファイル + メモリ常駐）
+    pub fn save_to_file(&self) -> Result<(), String> {
+        let data = PlaybackRatesData {
+            version: "1.0".to_string(),
+            tracks: self.track_rates.clone(),
+        };
+
+        let json = serde_json::to_string_pretty(&data)
+            .map_err(|e| format!("Failed to serialize playback rates data: {}", e))?;
+
+        std::fs::write(&self.rates_file, json)
+            .map_err(|e| format!("Failed to write playback rates file: {}", e))?;
+
+        Ok(())
+    }
+
+    pub fn load_from_file(&mut self) -> Result<(), String> {
+        if !self.rates_file.exists() {
+            // ファイルが存在しない場合は空のデータで初期化
+            self.track_rates = HashMap::new();
+            return Ok(());
+        }
+
+        let content = std::fs::read_to_string(&self.rates_file)
+            .map_err(|e| format!("Failed to read playback rates file: {}", e))?;
+
+        let data: PlaybackRatesData = serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse playback rates data: {}", e))?;
+
+        self.track_rates = data.tracks;
+        Ok(())
+    }
+
+    // ファイルパス管理
+    fn get_rates_file_path() -> PathBuf {
+        let mut path = std::env::current_exe()
+            .unwrap_or_else(|_| PathBuf::from("."))
+            .parent()
+            .unwrap_or(&PathBuf::from("."))
+            .to_path_buf();
+
+        path.push("playback_rates.json");
+        path
+    }
+}
@@ -0,0 +1,98 @@
+use std::time::{Duration, Instant};
+
+/// シーク要求がエンジンに反映されるまでの許容誤差（秒）
+const SEEK_TOLERANCE_SECONDS: f64 = 0.2;
+/// シーク要求の反映を待つ最大時間
+const SEEK_TIMEOUT: Duration = Duration::from_secs(1);
+/// 出力バッファ長として扱う位置更新間隔の上限（秒）
+const MAX_BUFFER_SECONDS: f64 = 0.2;
+
+/// オーディオエンジンが報告する再生位置から表示用の再生位置を推定する
+///
+/// kiraの位置は出力バッファ単位でしか更新されず、かつ実際に聞こえている音より
+/// 1バッファ分先行している。位置の更新間隔の最小値をバッファ長とみなし、
+/// 更新間の補間と遅延補正の両方に用いる。再生速度を変更している場合は
+/// バッファ長を実時間で保持し、楽曲上の位置に換算する際に速度を掛ける。
+#[derive(Debug, Clone, Copy)]
+pub struct PositionTracker {
+    anchor_position: f64,                  // 最後に観測したエンジンの位置（秒）
+    anchor_time: Option<Instant>,          // 位置の変化を観測した時刻
+    origin: f64,                           // 再生開始・シーク完了時の位置（これより前には戻さない）
+    buffer_seconds: Option<f64>,           // 推定した出力バッファ長（実時間の秒）
+    playback_rate: f64,                    // 再生速度（1.0で等速）
+    pending_seek: Option<(f64, Instant)>,  // 反映待ちのシーク先（秒, 要求時刻）
+}
+
+impl PositionTracker {
+    pub fn new() -> Self {
+        Self {
+            anchor_position: 0.0,
+            anchor_time: None,
+            origin: 0.0,
+            buffer_seconds: None,
+            playback_rate: 1.0,
+            pending_seek: None,
+        }
+    }
+
+    /// 新しい楽曲の再生開始（推定済みのバッファ長は出力デバイス固有なので引き継ぐ）
+    pub fn reset(&mut self) {
+        *self = Self {
+            buffer_seconds: self.buffer_seconds,
+            playback_rate: self.playback_rate,
+            ..Self::new()
+        };
+    }
+
+    pub fn set_playback_rate(&mut self, playback_rate: f64) {
+        self.playback_rate = playback_rate;
+    }
+
+    /// シーク要求を記録（エンジンに反映されるまではシーク先を位置とする）
+    pub fn start_seek(&mut self, target_seconds: f64, now: Instant) {
+        self.pending_seek = Some((target_seconds, now));
+    }
+
+    /// シーク要求がエンジンに反映待ちかどうか
+    pub fn is_seeking(&self) -> bool {
+        self.pending_seek.is_some()
+    }
+
+    /// エンジンの位置を反映して推定位置（秒）を返す
+    pub fn update(&mut self, engine_position: f64, is_playing: bool, now: Instant) -> f64 {
+        if let Some((target, requested_at)) = self.pending_seek {
+            let reached = (engine_position - target).abs() <= SEEK_TOLERANCE_SECONDS;
+            if !reached && now.duration_since(requested_at) < SEEK_TIMEOUT {
+                return target;
+            }
+
+            self.pending_seek = None;
+            self.origin = engine_position;
+            self.anchor_position = engine_position;
+            self.anchor_time = Some(now);
+        }
+
+        if engine_position != self.anchor_position {
+            let step = (engine_position - self.anchor_position) / self.playback_rate;
+            if step < 0.0 {
+                // シーク以外で位置が戻った場合はA–Bリピートで区間の先頭に折り返した
+                self.origin = engine_position;
+            }
+            if is_playing && step > 0.0 && step < MAX_BUFFER_SECONDS {
+                self.buffer_seconds = Some(self.buffer_seconds.map_or(step, |buffer| buffer.min(step)));
+            }
+            self.anchor_position = engine_position;
+            self.anchor_time = Some(now);
+        }
+
+        let buffer = self.buffer_seconds.unwrap_or(0.0);
+
+        // 次の更新までの間は経過時間で補間（1バッファ分まで）
+        let elapsed = match (is_playing, self.anchor_time) {
+            (true, Some(anchor_time)) => now.duration_since(anchor_time).as_secs_f64().min(buffer),
+            _ => 0.0,
+        };
+
+        (self.anchor_position + (elapsed - buffer) * self.playback_rate).max(self.origin)
+    }
+}
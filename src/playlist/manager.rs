@@ -3,6 +3,7 @@ use std::time::SystemTime;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use crate::music::TrackInfo;
+use crate::music::replaygain::ReplayGain;
 use crate::settings::RepeatMode;
 
 
@@ -90,10 +91,10 @@ pub struct PlaylistManager {
     pub(crate) playlists: Vec<Playlist>,
     pub(crate) active_playlist_id: String,
     selected_indices: HashSet<usize>,
-    current_playing_index: Option<usize>,
+    pub(crate) current_playing_index: Option<usize>,
     pub(crate) current_playing_playlist_id: Option<String>, // 現在再生中の楽曲があるプレイリスト
-    shuffle_order: Vec<usize>, // シャッフル時の再生順序
-    shuffle_position: Option<usize>, // シャッフル順序内での現在位置
+    pub(crate) shuffle_order: Vec<usize>, // シャッフル時の再生順序
+    pub(crate) shuffle_position: Option<usize>, // シャッフル順序内での現在位置
     last_selected_index: Option<usize>, // 範囲選択用の最後に選択されたインデックス
 }
 
@@ -842,6 +843,17 @@ impl PlaylistManager {
         self.playlists.shrink_to_fit();
     }
 
+    /// 解析したReplayGainを全プレイリストの該当楽曲に反映
+    pub fn update_replay_gain(&mut self, gains: &std::collections::HashMap<std::path::PathBuf, ReplayGain>) {
+        for playlist in &mut self.playlists {
+            for track in &mut playlist.tracks {
+                if let Some(replay_gain) = gains.get(&track.path) {
+                    track.replay_gain = Some(*replay_gain);
+                }
+            }
+        }
+    }
+
     /// 高速プレイリスト統計（メタデータ無し）
     pub fn get_quick_stats(&self) -> (usize, usize) {
         let total_playlists = self.playlists.len();
@@ -1010,6 +1022,52 @@ impl PlaylistManager {
         None
     }
 
+    /// 再生位置を変えずに次に再生される楽曲を取得（ギャップレス再生の先読み用）
+    ///
+    /// シャッフル順序の作り直しが必要な場合は次の楽曲が確定しないためNoneを返す。
+    #[allow(dead_code)]
+    pub fn peek_next_with_modes(&self, repeat_mode: &RepeatMode, shuffle_enabled: bool) -> Option<TrackInfo> {
+        self.peek_next_track_with_modes(repeat_mode, shuffle_enabled).cloned()
+    }
+
+    /// `peek_next_with_modes` の参照版（毎フレーム呼んでもプレイリストや楽曲を複製しない）
+    pub fn peek_next_track_with_modes(&self, repeat_mode: &RepeatMode, shuffle_enabled: bool) -> Option<&TrackInfo> {
+        let playlist = self.get_current_playing_playlist()?;
+        let index = self.next_index_with_modes(repeat_mode, shuffle_enabled, playlist.tracks.len())?;
+        playlist.tracks.get(index)
+    }
+
+    /// `move_to_next_with_modes` で進む先のインデックスを、状態を変えずに求める
+    fn next_index_with_modes(&self, repeat_mode: &RepeatMode, shuffle_enabled: bool, track_count: usize) -> Option<usize> {
+        if track_count == 0 {
+            return None;
+        }
+
+        if shuffle_enabled {
+            // シャッフル順序が未作成の場合は作成時に決まる
+            if self.shuffle_order.is_empty() {
+                return None;
+            }
+            let Some(current_pos) = self.shuffle_position else {
+                return self.shuffle_order.first().copied();
+            };
+            match repeat_mode {
+                RepeatMode::RepeatOne => self.shuffle_order.get(current_pos).copied(),
+                // 順序の最後では、リピート時は作り直した順序の先頭、リピートなしは停止
+                _ => self.shuffle_order.get(current_pos + 1).copied(),
+            }
+        } else {
+            let Some(current_index) = self.current_playing_index else {
+                return Some(0);
+            };
+            match repeat_mode {
+                RepeatMode::RepeatOne => Some(current_index),
+                RepeatMode::RepeatAll => Some(if current_index + 1 < track_count { current_index + 1 } else { 0 }),
+                RepeatMode::Normal => (current_index + 1 < track_count).then_some(current_index + 1),
+            }
+        }
+    }
+
     pub fn move_to_previous_with_modes(&mut self, shuffle_enabled: bool) -> Option<TrackInfo> {
         let playlist = self.get_current_playing_playlist()?.clone();
         let track_count = playlist.tracks.len();
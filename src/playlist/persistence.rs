@@ -9,6 +9,12 @@ struct PlaylistsData {
     active_playlist_id: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     current_playing_playlist_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    current_playing_index: Option<usize>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    shuffle_order: Vec<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    shuffle_position: Option<usize>,
 }
 
 impl PlaylistManager {
@@ -17,6 +23,9 @@ impl PlaylistManager {
             playlists: self.playlists.clone(),
             active_playlist_id: self.active_playlist_id.clone(),
             current_playing_playlist_id: self.current_playing_playlist_id.clone(),
+            current_playing_index: self.current_playing_index,
+            shuffle_order: self.shuffle_order.clone(),
+            shuffle_position: self.shuffle_position,
         };
         
         // Step 4-2: JSON生成のエラーハンドリング
@@ -95,6 +104,9 @@ impl PlaylistManager {
             }
         };
         
+        // 以前の形式ではカバーアートの画像データを楽曲ごとに保存していた
+        let has_inline_cover_art = contains_inline_cover_art(&json_data);
+
         // Step 4-2: データの検証とクリーンアップ
         if data.playlists.is_empty() {
             // プレイリストが空の場合はデフォルトを作成
@@ -102,6 +114,13 @@ impl PlaylistManager {
             return Ok(PlaylistManager::new());
         }
 
+        // 存在しない楽曲の削除でインデックスがずれても同じ楽曲を指せるよう、再生中の楽曲のパスを控える
+        let playing_track_path = data.current_playing_playlist_id.as_ref()
+            .and_then(|id| data.playlists.iter().find(|p| &p.id == id))
+            .zip(data.current_playing_index)
+            .and_then(|(playlist, index)| playlist.tracks.get(index))
+            .map(|track| track.path.clone());
+
         // プレイリストの検証とクリーンアップ
         let mut playlists = Vec::new();
         let mut invalid_playlists = Vec::new();
@@ -199,10 +218,32 @@ impl PlaylistManager {
         manager.playlists = playlists;
         manager.active_playlist_id = active_playlist_id;
         
-        // 現在再生中のプレイリストIDを復元（存在チェック）
+        // 現在再生中のプレイリストIDと楽曲の位置を復元（存在チェック）
         if let Some(playing_id) = data.current_playing_playlist_id {
-            if manager.playlists.iter().any(|p| p.id == playing_id) {
+            if let Some(playing_playlist) = manager.playlists.iter().find(|p| p.id == playing_id) {
+                let track_count = playing_playlist.tracks.len();
+                manager.current_playing_index = playing_track_path.and_then(|path| {
+                    data.current_playing_index
+                        .filter(|&index| playing_playlist.tracks.get(index).is_some_and(|track| track.path == path))
+                        .or_else(|| playing_playlist.tracks.iter().position(|track| track.path == path))
+                });
                 manager.current_playing_playlist_id = Some(playing_id);
+                
+                // シャッフル順序はプレイリストの楽曲数と一致する場合のみ復元
+                let mut sorted_order = data.shuffle_order.clone();
+                sorted_order.sort_unstable();
+                if sorted_order.into_iter().eq(0..track_count) && track_count > 0 {
+                    manager.shuffle_order = data.shuffle_order;
+                    manager.shuffle_position = data.shuffle_position.filter(|&pos| pos < track_count);
+                }
+            }
+        }
+
+        // 画像データを含まない形式で保存し直してファイルを小さくする
+        if has_inline_cover_art {
+            eprintln!("Info: Removing inline cover art from playlist file '{}'", path.display());
+            if let Err(e) = manager.save_to_file(path) {
+                eprintln!("Warning: Failed to rewrite playlist file without cover art: {}", e);
             }
         }
         
@@ -225,4 +266,10 @@ impl PlaylistManager {
         let path = Self::get_default_playlist_file_path();
         Self::load_from_file(&path)
     }
-}
\ No newline at end of file
+}
+
+/// プレイリストのJSONに画像データ（"cover_art": [...]）が含まれているかどうか
+fn contains_inline_cover_art(json: &str) -> bool {
+    json.match_indices("\"cover_art\":")
+        .any(|(index, key)| json[index + key.len()..].trim_start().starts_with('['))
+}
@@ -1,4 +1,6 @@
 use super::data::{SeekPoint, SeekPointsData};
+use crate::lyrics::Lyrics;
+use crate::lyrics::data::LyricLine;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
@@ -86,6 +88,52 @@ impl SeekPointManager {
             .find(|sp| sp.position_ms < current_ms)
     }
 
+    // LRC変換（シークポイント名を歌詞テキストとして扱う）
+    pub fn export_as_lyrics(&self, track_path: &Path) -> Option<Lyrics> {
+        let seek_points = self.track_seek_points.get(track_path)?;
+        if seek_points.is_empty() {
+            return None;
+        }
+
+        let lines = seek_points.iter()
+            .map(|sp| LyricLine::new(Some(sp.position_ms), sp.name.clone()))
+            .collect();
+
+        Some(Lyrics {
+            lines,
+            ..Lyrics::default()
+        })
+    }
+
+    /// 同期歌詞の各行をシークポイントとして追加し、追加件数を返す
+    ///
+    /// 空行と、既存シークポイントと同じ位置の行は追加しない。
+    /// `extra_offset_ms` は楽曲ごとのタイミング補正（正の値で歌詞を早める）。
+    pub fn import_from_lyrics(&mut self, track_path: &Path, lyrics: &Lyrics, extra_offset_ms: i64) -> usize {
+        let seek_points = self.track_seek_points.entry(track_path.to_path_buf()).or_default();
+        let mut added = 0;
+
+        for line in &lyrics.lines {
+            let time_ms = match lyrics.effective_time_ms(line) {
+                Some(time_ms) => (time_ms as i64 - extra_offset_ms).max(0) as u64,
+                None => continue,
+            };
+            let name = line.text.trim();
+            if name.is_empty() || seek_points.iter().any(|sp| sp.position_ms == time_ms) {
+                continue;
+            }
+
+            seek_points.push(SeekPoint::new(name.to_string(), time_ms));
+            added += 1;
+        }
+
+        seek_points.sort_by_key(|sp| sp.position_ms);
+        if seek_points.is_empty() {
+            self.track_seek_points.remove(track_path);
+        }
+        added
+    }
+
     // 永続化（単一JSONファイル + メモリ常駐）
     pub fn save_to_file(&self) -> Result<(), String> {
         let data = SeekPointsData {
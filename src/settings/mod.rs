@@ -2,16 +2,75 @@ use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
 
-#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
 pub enum RepeatMode {
+    #[default]
     Normal,         // 通常再生（リピートなし）
     RepeatOne,      // 1曲リピート
     RepeatAll,      // プレイリストリピート
 }
 
+/// クロスフェードの音量変化カーブ
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug, Default)]
+pub enum CrossfadeCurve {
+    #[default]
+    Linear,         // 一定の速さで変化
+    SCurve,         // 始めと終わりを緩やかに変化
+}
+
+impl CrossfadeCurve {
+    pub fn display_name(self) -> &'static str {
+        match self {
+            CrossfadeCurve::Linear => "リニア",
+            CrossfadeCurve::SCurve => "S字カーブ",
+        }
+    }
+}
+
+/// ラウドネス正規化（ReplayGain）のモード
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug, Default)]
+pub enum ReplayGainMode {
+    #[default]
+    Off,
+    Track,          // 楽曲ごとに音量を揃える
+    Album,          // アルバム内の音量差を保つ
+}
+
+impl ReplayGainMode {
+    pub fn display_name(self) -> &'static str {
+        match self {
+            ReplayGainMode::Off => "オフ",
+            ReplayGainMode::Track => "トラック",
+            ReplayGainMode::Album => "アルバム",
+        }
+    }
+}
+
+/// 音楽フォルダ（ライブラリのルート）
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+pub struct LibraryRoot {
+    pub path: String,
+    #[serde(default)]
+    pub exclude_patterns: Vec<String>,      // ルートからの相対パスに対するglobパターン（例: **/_incoming/**）
+    #[serde(default)]
+    pub follow_symlinks: bool,              // シンボリックリンク先のディレクトリもスキャンする
+}
+
+impl LibraryRoot {
+    pub fn new(path: String) -> Self {
+        Self {
+            path,
+            ..Default::default()
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct Settings {
-    pub target_directory: String,
+    #[serde(default, skip_serializing)]
+    target_directory: String,               // 旧形式の単一の音楽フォルダ（読み込み時にlibrary_rootsへ移行）
+    #[serde(default)]
+    pub library_roots: Vec<LibraryRoot>,
     pub classical_composer_hierarchy: bool,
     
     // Step 4-1: プレイリスト関連設定
@@ -30,6 +89,50 @@ pub struct Settings {
     // シーク機能設定
     pub seek_seconds: u32,                  // シーク秒数（1-60）
     
+    // クロスフェード設定（既存の設定ファイルを読み込めるよう省略時は既定値）
+    #[serde(default)]
+    pub crossfade_seconds: f32,             // 曲間のクロスフェード秒数（0で無効、0-10）
+    #[serde(default)]
+    pub crossfade_curve: CrossfadeCurve,
+    
+    // ReplayGain設定
+    #[serde(default)]
+    pub replay_gain_mode: ReplayGainMode,
+    #[serde(default)]
+    pub replay_gain_preamp_db: f32,         // プリアンプ（-15〜+15dB）
+    #[serde(default = "default_true")]
+    pub replay_gain_prevent_clipping: bool, // ピーク値によるクリッピング防止
+    #[serde(default)]
+    pub replay_gain_write_tags: bool,       // ラウドネス解析の結果をFLACのタグに書き込む
+    
+    // 音量設定
+    #[serde(default = "default_volume")]
+    pub volume: f32,                        // 出力音量（0.0-1.0）
+    #[serde(default)]
+    pub muted: bool,
+    
+    // 出力デバイス設定
+    #[serde(default)]
+    pub output_device: Option<String>,      // 出力デバイス名（Noneの場合はシステムの既定のデバイス）
+    
+    // 再生速度設定（速度自体はセッション中のみ保持）
+    #[serde(default = "default_true")]
+    pub preserve_pitch: bool,               // 速度変更時に音程を保つ
+    #[serde(default)]
+    pub remember_playback_rate_per_track: bool, // 楽曲ごとに再生速度を記憶
+    
+    // 前回終了時の再生状態（再生中だった楽曲とキューはプレイリストファイルに保存）
+    #[serde(default)]
+    pub repeat_mode: RepeatMode,
+    #[serde(default)]
+    pub shuffle_enabled: bool,
+    #[serde(default)]
+    pub resume_position_ms: u64,            // 再生中だった楽曲の再生位置
+    
+    // カバーアート設定
+    #[serde(default = "crate::music::cover_art::default_folder_image_names")]
+    pub cover_art_file_names: Vec<String>,  // 埋め込み画像が無い場合に探すフォルダ内の画像名（優先順、`*`を使用可）
+    
     // テーマ設定
     pub dark_mode: bool,                    // ダークモード（デフォルト: false）
     
@@ -47,6 +150,7 @@ impl Default for Settings {
     fn default() -> Self {
         Self {
             target_directory: String::new(),
+            library_roots: Vec::new(),
             classical_composer_hierarchy: false,
             last_used_playlist_id: None,
             playlist_display_order: vec!["default".to_string()],
@@ -56,12 +160,35 @@ impl Default for Settings {
             right_top_bottom_position: 0.4,
             right_bottom_left_right_position: 0.3,
             seek_seconds: 10,
+            crossfade_seconds: 0.0,
+            crossfade_curve: CrossfadeCurve::default(),
+            replay_gain_mode: ReplayGainMode::default(),
+            replay_gain_preamp_db: 0.0,
+            replay_gain_prevent_clipping: true,
+            replay_gain_write_tags: false,
+            volume: default_volume(),
+            muted: false,
+            output_device: None,
+            preserve_pitch: true,
+            remember_playback_rate_per_track: false,
+            repeat_mode: RepeatMode::default(),
+            shuffle_enabled: false,
+            resume_position_ms: 0,
+            cover_art_file_names: crate::music::cover_art::default_folder_image_names(),
             dark_mode: false,
             debug_ui_regions: false,
         }
     }
 }
 
+fn default_true() -> bool {
+    true
+}
+
+fn default_volume() -> f32 {
+    1.0
+}
+
 impl Default for DefaultPlaylistSettings {
     fn default() -> Self {
         Self {
@@ -74,7 +201,9 @@ impl Settings {
     pub fn load() -> Self {
         let settings_path = Self::get_settings_file_path();
         if let Ok(contents) = fs::read_to_string(&settings_path) {
-            serde_json::from_str(&contents).unwrap_or_default()
+            let mut settings: Settings = serde_json::from_str(&contents).unwrap_or_default();
+            settings.migrate_target_directory();
+            settings
         } else {
             Settings::default()
         }
@@ -93,15 +222,28 @@ impl Settings {
         path
     }
 
+    /// 旧形式の単一の音楽フォルダを音楽フォルダの一覧に移す
+    pub fn migrate_target_directory(&mut self) {
+        let target_directory = std::mem::take(&mut self.target_directory);
+        if target_directory.is_empty() || !self.library_roots.is_empty() {
+            return;
+        }
+        // 以前はシンボリックリンク先も常にスキャンしていたため、その動作を保つ
+        self.library_roots.push(LibraryRoot {
+            follow_symlinks: true,
+            ..LibraryRoot::new(target_directory)
+        });
+    }
+
     #[allow(dead_code)]
     pub fn validate(&self) -> Result<(), String> {
-        if !self.target_directory.is_empty() {
-            let path = PathBuf::from(&self.target_directory);
+        for root in &self.library_roots {
+            let path = PathBuf::from(&root.path);
             if !path.exists() {
-                return Err("Target directory does not exist".to_string());
+                return Err(format!("Library folder does not exist: {}", root.path));
             }
             if !path.is_dir() {
-                return Err("Target path is not a directory".to_string());
+                return Err(format!("Library path is not a directory: {}", root.path));
             }
         }
         Ok(())
@@ -216,6 +358,35 @@ impl Settings {
         self.seek_seconds
     }
 
+    // クロスフェード設定メソッド
+    pub fn set_crossfade_seconds(&mut self, seconds: f32) {
+        self.crossfade_seconds = seconds.clamp(0.0, 10.0);
+    }
+
+    pub fn get_crossfade_duration(&self) -> std::time::Duration {
+        if !self.crossfade_seconds.is_finite() {
+            return std::time::Duration::ZERO;
+        }
+        std::time::Duration::from_secs_f32(self.crossfade_seconds.clamp(0.0, 10.0))
+    }
+
+    // ReplayGain設定メソッド
+    pub fn set_replay_gain_preamp_db(&mut self, preamp_db: f32) {
+        self.replay_gain_preamp_db = preamp_db.clamp(-15.0, 15.0);
+    }
+
+    // 音量設定メソッド
+    pub fn set_volume(&mut self, volume: f32) {
+        self.volume = if volume.is_nan() { default_volume() } else { volume.clamp(0.0, 1.0) };
+    }
+
+    pub fn get_volume(&self) -> f32 {
+        if self.volume.is_nan() {
+            return default_volume();
+        }
+        self.volume.clamp(0.0, 1.0)
+    }
+
     // テーマ設定メソッド
     pub fn set_dark_mode(&mut self, dark_mode: bool) {
         self.dark_mode = dark_mode;
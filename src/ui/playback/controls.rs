@@ -1,5 +1,7 @@
-use crate::player::PlaybackState;
+use crate::player::{AbLoop, AbLoopPoint, PlaybackState};
+use crate::player::audio::{MAX_PLAYBACK_RATE, MIN_PLAYBACK_RATE};
 use crate::settings::RepeatMode;
+use crate::utils::formatting::TimeFormatter;
 use eframe::egui;
 
 pub struct PlaybackButtonsUI;
@@ -147,4 +149,111 @@ impl PlaybackButtonsUI {
                 });
         });
     }
-}
\ No newline at end of file
+
+    pub fn show_volume_control(
+        ui: &mut egui::Ui,
+        volume: f32,
+        muted: bool,
+        on_volume_change: &mut dyn FnMut(f32),
+        on_mute_toggle: &mut dyn FnMut(),
+    ) {
+        ui.horizontal(|ui| {
+            ui.add_space(5.0);
+
+            // ミュート切り替えボタン
+            let mute_text = if muted || volume <= 0.0 { "🔇" } else { "🔊" };
+            let mute_tooltip = if muted { "ミュート解除 (M)" } else { "ミュート (M)" };
+            if ui.add_sized([28.0, 20.0], egui::Button::new(mute_text))
+                .on_hover_text(mute_tooltip)
+                .clicked()
+            {
+                on_mute_toggle();
+            }
+
+            ui.add_space(5.0);
+
+            // 音量スライダー（0-100%）
+            let mut new_volume = volume;
+            let response = ui.add_enabled(
+                !muted,
+                egui::Slider::new(&mut new_volume, 0.0..=1.0)
+                    .show_value(false)
+                    .text(format!("{:>3}%", (volume * 100.0).round() as u32)),
+            ).on_hover_text("音量 (Ctrl+↑ / Ctrl+↓)");
+            if response.changed() {
+                on_volume_change(new_volume);
+            }
+        });
+    }
+
+    pub fn show_playback_rate_control(
+        ui: &mut egui::Ui,
+        playback_rate: f64,
+        preserve_pitch: bool,
+        on_playback_rate_change: &mut dyn FnMut(f64),
+        on_preserve_pitch_change: &mut dyn FnMut(bool),
+    ) {
+        ui.horizontal(|ui| {
+            ui.add_space(5.0);
+
+            ui.label("速度:");
+
+            let mut new_playback_rate = playback_rate;
+            if ui.add(
+                egui::Slider::new(&mut new_playback_rate, MIN_PLAYBACK_RATE..=MAX_PLAYBACK_RATE)
+                    .step_by(0.05)
+                    .fixed_decimals(2)
+                    .suffix("x"),
+            ).changed() {
+                on_playback_rate_change(new_playback_rate);
+            }
+
+            // 等速に戻す
+            if ui.add_enabled(playback_rate != 1.0, egui::Button::new("1.0x")).clicked() {
+                on_playback_rate_change(1.0);
+            }
+
+            let mut new_preserve_pitch = preserve_pitch;
+            if ui.checkbox(&mut new_preserve_pitch, "音程を保つ").changed() {
+                on_preserve_pitch_change(new_preserve_pitch);
+            }
+        });
+    }
+
+    pub fn show_ab_loop_controls(
+        ui: &mut egui::Ui,
+        ab_loop: &AbLoop,
+        on_set_point: &mut dyn FnMut(AbLoopPoint),
+        on_clear: &mut dyn FnMut(),
+    ) {
+        ui.horizontal(|ui| {
+            ui.add_space(5.0);
+
+            ui.label("A–B:");
+
+            // 現在位置を開始点・終了点に設定
+            if ui.button("A").on_hover_text("現在位置を開始点に設定").clicked() {
+                on_set_point(AbLoopPoint::A);
+            }
+            if ui.button("B").on_hover_text("現在位置を終了点に設定").clicked() {
+                on_set_point(AbLoopPoint::B);
+            }
+            if ui.add_enabled(!ab_loop.is_empty(), egui::Button::new("解除")).clicked() {
+                on_clear();
+            }
+
+            ui.add_space(5.0);
+
+            let format_point = |point: Option<std::time::Duration>| {
+                point.map(TimeFormatter::format_duration).unwrap_or_else(|| "--:--".to_string())
+            };
+            let status = format!("{} – {}", format_point(ab_loop.point_a()), format_point(ab_loop.point_b()));
+            let status = egui::RichText::new(status).size(12.0);
+            if ab_loop.region().is_some() {
+                ui.label(status.color(egui::Color32::from_rgb(255, 150, 0)));
+            } else {
+                ui.label(status.weak());
+            }
+        });
+    }
+}
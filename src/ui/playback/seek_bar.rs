@@ -1,3 +1,4 @@
+use crate::player::AbLoop;
 use crate::seek_points::SeekPoint;
 use crate::utils::formatting::TimeFormatter;
 use eframe::egui;
@@ -5,11 +6,13 @@ use eframe::egui;
 pub struct SeekBarUI;
 
 impl SeekBarUI {
+    #[allow(clippy::too_many_arguments)]
     pub fn show(
         ui: &mut egui::Ui,
         current_position: std::time::Duration,
         total_duration: Option<std::time::Duration>,
         seek_points: Option<&Vec<SeekPoint>>,
+        ab_loop: &AbLoop,
         on_seek: &mut dyn FnMut(std::time::Duration),
         on_seek_start: &mut dyn FnMut(),
         on_seek_end: &mut dyn FnMut(),
@@ -51,6 +54,9 @@ impl SeekBarUI {
                     ui.painter().rect_filled(progress_rect, 4.0, fill_color);
                 }
 
+                // A–Bリピートの区間を表示
+                Self::draw_ab_loop(ui, rect, total, ab_loop);
+
                 // 現在の再生位置を赤い線で表示
                 if progress > 0.0 {
                     let position_x = rect.left() + rect.width() * progress as f32;
@@ -192,6 +198,41 @@ impl SeekBarUI {
         });
     }
 
+    /// A–Bリピートの区間（片方のみ設定されている場合はその位置）を描画
+    fn draw_ab_loop(ui: &egui::Ui, rect: egui::Rect, total: std::time::Duration, ab_loop: &AbLoop) {
+        if total.is_zero() {
+            return;
+        }
+        let x_of = |position: std::time::Duration| {
+            let progress = (position.as_secs_f64() / total.as_secs_f64()).clamp(0.0, 1.0);
+            rect.left() + rect.width() * progress as f32
+        };
+        let loop_color = egui::Color32::from_rgb(255, 150, 0);
+
+        if let Some((a, b)) = ab_loop.region() {
+            let region_rect = egui::Rect::from_x_y_ranges(x_of(a)..=x_of(b), rect.y_range());
+            ui.painter().rect_filled(region_rect, 0.0, egui::Color32::from_rgba_unmultiplied(255, 150, 0, 60));
+        }
+
+        for (label, position) in [("A", ab_loop.point_a()), ("B", ab_loop.point_b())] {
+            let Some(position) = position else {
+                continue;
+            };
+            let x = x_of(position);
+            ui.painter().line_segment(
+                [egui::pos2(x, rect.top()), egui::pos2(x, rect.bottom())],
+                egui::Stroke::new(2.0, loop_color),
+            );
+            ui.painter().text(
+                egui::pos2(x, rect.bottom()),
+                egui::Align2::CENTER_TOP,
+                label,
+                egui::FontId::proportional(10.0),
+                loop_color,
+            );
+        }
+    }
+
     fn format_duration(duration: std::time::Duration) -> String {
         TimeFormatter::format_duration(duration)
     }
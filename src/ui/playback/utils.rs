@@ -1,4 +1,5 @@
 use crate::music::TrackInfo;
+use crate::player::AbLoopPoint;
 use crate::seek_points::SeekPoint;
 use crate::utils::formatting::TimeFormatter;
 use eframe::egui;
@@ -14,6 +15,7 @@ impl PlaybackUtils {
         ui: &mut egui::Ui,
         seek_points: Option<&Vec<SeekPoint>>,
         on_seek_to_point: &mut dyn FnMut(u64),
+        mut on_set_loop_point: Option<&mut dyn FnMut(AbLoopPoint, u64)>,
     ) {
         if let Some(points) = seek_points {
             if !points.is_empty() {
@@ -67,7 +69,19 @@ impl PlaybackUtils {
                                     let minutes = (total_seconds / 60.0) as u32;
                                     let seconds = total_seconds % 60.0;
                                     let time_text = format!("{:02}:{:06.3}", minutes, seconds);
-                                    ui.label(egui::RichText::new(&time_text).size(12.0).color(egui::Color32::from_gray(200)));
+                                    ui.horizontal(|ui| {
+                                        ui.label(egui::RichText::new(&time_text).size(12.0).color(egui::Color32::from_gray(200)));
+
+                                        // A–Bリピートの開始点・終了点に設定
+                                        if let Some(on_set_loop_point) = on_set_loop_point.as_mut() {
+                                            if ui.small_button("A").on_hover_text("A–Bリピートの開始点に設定").clicked() {
+                                                on_set_loop_point(AbLoopPoint::A, seek_point.position_ms);
+                                            }
+                                            if ui.small_button("B").on_hover_text("A–Bリピートの終了点に設定").clicked() {
+                                                on_set_loop_point(AbLoopPoint::B, seek_point.position_ms);
+                                            }
+                                        }
+                                    });
 
                                     ui.end_row();
                                 }
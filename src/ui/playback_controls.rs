@@ -1,4 +1,4 @@
-use crate::player::PlaybackState;
+use crate::player::{AbLoop, AbLoopPoint, PlaybackState};
 use crate::music::TrackInfo;
 use crate::settings::RepeatMode;
 use crate::seek_points::SeekPoint;
@@ -83,7 +83,7 @@ impl PlaybackControlsUI {
         on_seek_to_point: &mut dyn FnMut(u64), // シークポイントジャンプ用コールバック
     ) {
         // シークバーを最初に表示（横幅全体を使用）
-        SeekBarUI::show(ui, current_position, total_duration, seek_points, on_seek, on_seek_start, on_seek_end);
+        SeekBarUI::show(ui, current_position, total_duration, seek_points, &AbLoop::new(), on_seek, on_seek_start, on_seek_end);
         
         ui.add_space(10.0);
 
@@ -144,7 +144,7 @@ impl PlaybackControlsUI {
                         ui.add_space(15.0);
 
                         // シークポイント一覧（残りの高さをすべて使用）
-                        PlaybackUtils::show_current_track_seek_points(ui, seek_points, on_seek_to_point);
+                        PlaybackUtils::show_current_track_seek_points(ui, seek_points, on_seek_to_point, None);
                     } else {
                         ui.label("楽曲が選択されていません");
                     }
@@ -157,8 +157,9 @@ impl PlaybackControlsUI {
         ui: &mut egui::Ui,
         seek_points: Option<&Vec<SeekPoint>>,
         on_seek_to_point: &mut dyn FnMut(u64),
+        on_set_loop_point: Option<&mut dyn FnMut(AbLoopPoint, u64)>,
     ) {
-        PlaybackUtils::show_current_track_seek_points(ui, seek_points, on_seek_to_point);
+        PlaybackUtils::show_current_track_seek_points(ui, seek_points, on_seek_to_point, on_set_loop_point);
     }
 
     #[allow(dead_code)]
@@ -358,11 +359,13 @@ impl PlaybackControlsUI {
         );
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn show_seek_bar(
         ui: &mut egui::Ui,
         current_position: std::time::Duration,
         total_duration: Option<std::time::Duration>,
         seek_points: Option<&Vec<SeekPoint>>,
+        ab_loop: &AbLoop,
         on_seek: &mut dyn FnMut(std::time::Duration),
         on_seek_start: &mut dyn FnMut(),
         on_seek_end: &mut dyn FnMut(),
@@ -372,6 +375,7 @@ impl PlaybackControlsUI {
             current_position,
             total_duration,
             seek_points,
+            ab_loop,
             on_seek,
             on_seek_start,
             on_seek_end,
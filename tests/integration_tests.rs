@@ -1,4 +1,36 @@
 use flac_music_player::app::{MyApp, Tab, RightTab};
+use std::path::{Path, PathBuf};
+
+/// テスト用の一時ディレクトリ（テストが失敗した場合も破棄時に削除する）
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+    pub fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("{}_{}", name, std::process::id()));
+        // 以前の実行で残ったファイルは消してから使う
+        let _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(&path).unwrap();
+        Self(path)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl std::ops::Deref for TempDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
 
 #[cfg(test)]
 mod app_tests {
@@ -96,6 +128,37 @@ mod app_tests {
         // 変更が正しく反映されることを確認
         assert_eq!(app.player_state.shuffle_enabled, true);
     }
+
+    #[test]
+    fn test_lyrics_offset_shortcut_follows_playing_track() {
+        use flac_music_player::lyrics::LyricsOffsetManager;
+        use flac_music_player::playlist::PlaylistManager;
+
+        let dir = super::TempDir::new("lyrics_offset_shortcut_test");
+        let mut app = MyApp::new();
+        app.lyrics_state.offset_manager = LyricsOffsetManager::with_file_path(dir.join("lyrics_offsets.json"));
+        app.playlist_manager = PlaylistManager::new();
+        let first = super::playlist_tests::create_track("first");
+        let second = super::playlist_tests::create_track("second");
+        app.playlist_manager.add_track(first.clone());
+        app.playlist_manager.add_track(second.clone());
+
+        // 歌詞タブを一度も表示していなくても、再生中の楽曲の補正を変更する
+        app.playlist_manager.set_current_playing_index(Some(0));
+        app.handle_lyrics_offset_adjust(100);
+        app.playlist_manager.set_current_playing_index(Some(1));
+        app.handle_lyrics_offset_adjust(-200);
+
+        let offsets = &app.lyrics_state.offset_manager;
+        assert_eq!(offsets.get_offset(&first.path), 100);
+        assert_eq!(offsets.get_offset(&second.path), -200);
+        assert_eq!(app.lyrics_state.track_path.as_ref(), Some(&second.path));
+        assert_eq!(app.lyrics_state.user_offset_ms(), -200);
+
+        app.handle_lyrics_offset_reset();
+        assert_eq!(app.lyrics_state.offset_manager.get_offset(&second.path), 0);
+        assert_eq!(app.lyrics_state.offset_manager.get_offset(&first.path), 100);
+    }
 }
 
 #[cfg(test)]
@@ -252,6 +315,31 @@ mod seek_points_tests {
         
         _cleanup();
     }
+
+    #[test]
+    fn test_seek_points_lrc_conversion() {
+        use flac_music_player::lyrics::parser::LrcParser;
+
+        let mut manager = flac_music_player::seek_points::SeekPointManager::new();
+        let test_track = PathBuf::from("test_track_lrc_conversion.flac");
+
+        // オフセット適用後の時刻で取り込まれ、空行は除外される
+        let lyrics = LrcParser::parse("[offset:500]\n[00:10.50]イントロ\n[00:20.00]\n[01:00.00]サビ\n");
+        assert_eq!(manager.import_from_lyrics(&test_track, &lyrics, 0), 2);
+
+        let seek_points = manager.get_seek_points(&test_track).unwrap();
+        assert_eq!(seek_points[0].name, "イントロ");
+        assert_eq!(seek_points[0].position_ms, 10000);
+        assert_eq!(seek_points[1].position_ms, 59500);
+
+        // 同じ位置の行は重複して追加しない
+        assert_eq!(manager.import_from_lyrics(&test_track, &lyrics, 0), 0);
+
+        let exported = manager.export_as_lyrics(&test_track).unwrap();
+        assert_eq!(exported.lines.len(), 2);
+        assert_eq!(exported.lines[0].timestamp_ms, Some(10000));
+        assert_eq!(exported.lines[1].text, "サビ");
+    }
 }
 
 #[cfg(test)]
@@ -306,4 +394,1541 @@ mod playback_controls_layout_tests {
         // レイアウト計算の一貫性テスト（値が妥当な範囲内であることを確認）
         assert!(app.ui_state.splitter_position >= 0.1 && app.ui_state.splitter_position <= 0.9);
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod lyrics_tests {
+    use super::TempDir;
+    use flac_music_player::lyrics::LyricsLoader;
+    use flac_music_player::lyrics::parser::LrcParser;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_parse_timestamps() {
+        assert_eq!(LrcParser::parse_timestamp("00:12.34"), Some(12340));
+        assert_eq!(LrcParser::parse_timestamp("01:02.345"), Some(62345));
+        assert_eq!(LrcParser::parse_timestamp("03:04"), Some(184000));
+        assert_eq!(LrcParser::parse_timestamp("00:05:50"), Some(5500));
+        assert_eq!(LrcParser::parse_timestamp("ti:Title"), None);
+        assert_eq!(LrcParser::parse_timestamp("00:75.00"), None);
+    }
+
+    #[test]
+    fn test_parse_lrc_with_tags() {
+        let content = "\u{feff}[ti:テスト曲]\n[ar:テストアーティスト]\n[by:作成者]\n[offset:+200]\n\
+                       [00:10.00]一行目\n[00:20.50][01:00.00]繰り返し\n[00:30.00]\n";
+        let lyrics = LrcParser::parse(content);
+
+        assert_eq!(lyrics.metadata.title.as_deref(), Some("テスト曲"));
+        assert_eq!(lyrics.metadata.artist.as_deref(), Some("テストアーティスト"));
+        assert_eq!(lyrics.metadata.author.as_deref(), Some("作成者"));
+        assert_eq!(lyrics.offset_ms, 200);
+        assert!(lyrics.is_synced());
+
+        // 複数タイムスタンプの行は展開され時間順に並ぶ
+        let times: Vec<_> = lyrics.lines.iter().map(|l| l.timestamp_ms.unwrap()).collect();
+        assert_eq!(times, vec![10000, 20500, 30000, 60000]);
+        assert_eq!(lyrics.lines[1].text, "繰り返し");
+        assert_eq!(lyrics.lines[2].text, "");
+        assert_eq!(lyrics.lines[3].text, "繰り返し");
+    }
+
+    #[test]
+    fn test_find_current_line_with_offset() {
+        let lyrics = LrcParser::parse("[offset:500]\n[00:01.00]A\n[00:03.00]B\n[00:05.00]C\n");
+
+        assert_eq!(lyrics.find_current_line_index(0), None);
+        // オフセット500msにより1.0秒の行は0.5秒から表示
+        assert_eq!(lyrics.find_current_line_index(500), Some(0));
+        assert_eq!(lyrics.find_current_line_index(2499), Some(0));
+        assert_eq!(lyrics.find_current_line_index(2500), Some(1));
+        assert_eq!(lyrics.find_current_line_index(100000), Some(2));
+    }
+
+    #[test]
+    fn test_parse_plain_text_lyrics() {
+        let lyrics = LrcParser::parse("\n一行目\n\n二行目\n\n");
+
+        assert!(!lyrics.is_synced());
+        assert_eq!(lyrics.lines.len(), 3);
+        assert_eq!(lyrics.lines[0].text, "一行目");
+        assert_eq!(lyrics.lines[2].text, "二行目");
+        assert_eq!(lyrics.find_current_line_index(10000), None);
+    }
+
+    #[test]
+    fn test_find_sidecar_lrc_file() {
+        let dir = TempDir::new("lyrics_sidecar_test");
+
+        let track_path: PathBuf = dir.join("01. Song.flac");
+        let lrc_path = dir.join("01. Song.lrc");
+        std::fs::write(&lrc_path, "[00:01.00]歌詞\n").unwrap();
+
+        assert_eq!(LyricsLoader::find_lrc_file(&track_path), Some(lrc_path));
+        let lyrics = LyricsLoader::load_for_track(&track_path).unwrap();
+        assert_eq!(lyrics.lines.len(), 1);
+        assert_eq!(lyrics.lines[0].text, "歌詞");
+
+        assert!(LyricsLoader::find_lrc_file(&dir.join("02. Other.flac")).is_none());
+    }
+
+    #[test]
+    fn test_select_embedded_lyrics_prefers_synced() {
+        use flac_music_player::lyrics::LyricsSource;
+        use flac_music_player::music::EmbeddedLyrics;
+
+        let candidates = vec![
+            EmbeddedLyrics { field_name: "LYRICS".to_string(), text: "プレーン歌詞\n二行目".to_string() },
+            EmbeddedLyrics { field_name: "UNSYNCEDLYRICS".to_string(), text: "[00:01.00]同期歌詞".to_string() },
+        ];
+        let lyrics = LyricsLoader::select_embedded(candidates).unwrap();
+        assert!(lyrics.is_synced());
+        assert_eq!(lyrics.source, Some(LyricsSource::Embedded("UNSYNCEDLYRICS".to_string())));
+
+        // 同期歌詞がなければ優先順で最初のもの
+        let candidates = vec![
+            EmbeddedLyrics { field_name: "LYRICS".to_string(), text: "プレーン歌詞".to_string() },
+        ];
+        let lyrics = LyricsLoader::select_embedded(candidates).unwrap();
+        assert!(!lyrics.is_synced());
+        assert_eq!(lyrics.lines[0].text, "プレーン歌詞");
+
+        assert!(LyricsLoader::select_embedded(Vec::new()).is_none());
+    }
+
+    #[test]
+    fn test_parse_enhanced_lrc_word_timing() {
+        let lyrics = LrcParser::parse("[00:10.00]<00:10.00>歌い <00:11.00>出し<00:12.00>\n[00:14.00]次の行\n");
+
+        let line = &lyrics.lines[0];
+        assert!(line.has_word_timing());
+        assert_eq!(line.text, "歌い 出し");
+        assert_eq!(line.words.len(), 2);
+        assert_eq!(line.words[0].text, "歌い ");
+        assert_eq!(line.words[0].timestamp_ms, 10000);
+        assert_eq!(line.words[0].end_ms, Some(11000));
+        assert_eq!(line.words[1].end_ms, Some(12000));
+        assert!(!lyrics.lines[1].has_word_timing());
+
+        // 歌い終えた文字数（単語の途中は経過時間で按分）
+        assert_eq!(lyrics.sung_char_count(0, 9000), 0);
+        assert_eq!(lyrics.sung_char_count(0, 10500), 1);
+        assert_eq!(lyrics.sung_char_count(0, 11000), 3);
+        assert_eq!(lyrics.sung_char_count(0, 11500), 4);
+        assert_eq!(lyrics.sung_char_count(0, 13000), 5);
+    }
+
+    #[test]
+    fn test_enhanced_lrc_last_word_ends_at_next_line() {
+        let lyrics = LrcParser::parse("[00:01.00]<00:01.00>ab<00:02.00>cd\n[00:04.00]next\n");
+
+        assert_eq!(lyrics.lines[0].words[1].end_ms, None);
+        // 最後の単語は次の行の開始（4秒）まで
+        assert_eq!(lyrics.sung_char_count(0, 3000), 3);
+        assert_eq!(lyrics.sung_char_count(0, 4000), 4);
+    }
+
+    #[test]
+    fn test_sync_editor_stamp_nudge_undo() {
+        use flac_music_player::lyrics::LyricsSyncEditor;
+
+        let track_path = PathBuf::from("/music/song.flac");
+        let mut editor = LyricsSyncEditor::new(&track_path, None);
+        assert!(editor.is_text_input());
+
+        editor.text_input = "一行目\n二行目\n三行目".to_string();
+        editor.import_text();
+        assert_eq!(editor.lines().len(), 3);
+        assert_eq!(editor.unstamped_count(), 3);
+
+        assert!(editor.stamp_next(1000));
+        assert!(editor.stamp_next(2500));
+        assert_eq!(editor.next_index(), 2);
+        assert_eq!(editor.lines()[1].timestamp_ms, Some(2500));
+
+        assert!(editor.nudge(1, -100));
+        assert_eq!(editor.lines()[1].timestamp_ms, Some(2400));
+
+        assert!(editor.undo());
+        assert_eq!(editor.lines()[1].timestamp_ms, Some(2500));
+        assert!(editor.undo());
+        assert_eq!(editor.next_index(), 1);
+        assert_eq!(editor.lines()[1].timestamp_ms, None);
+
+        // 未設定の行の歌詞が失われないよう、全ての行を打刻するまで保存できない
+        assert!(!editor.can_save());
+        assert!(editor.stamp_next(2500));
+        assert!(editor.stamp_next(4000));
+        assert!(editor.can_save());
+        let lyrics = editor.to_lyrics();
+        assert_eq!(lyrics.lines.len(), 3);
+        assert_eq!(lyrics.lines[2].text, "三行目");
+    }
+
+    #[test]
+    fn test_save_lrc_round_trip() {
+        use flac_music_player::lyrics::LrcWriter;
+
+        let dir = TempDir::new("lyrics_writer_test");
+        let track_path = dir.join("01. Song.flac");
+
+        let original = LrcParser::parse("[ti:曲名]\n[offset:+200]\n[00:01.50]一行目\n[01:02.03]<01:02.03>二<01:03.00>行目\n");
+        let saved_path = LrcWriter::save_for_track(&track_path, &original).unwrap();
+        assert_eq!(saved_path, dir.join("01. Song.lrc"));
+        assert!(!dir.join("01. Song.lrc.tmp").exists());
+
+        let reloaded = LyricsLoader::load_for_track(&track_path).unwrap();
+        assert_eq!(reloaded.metadata, original.metadata);
+        assert_eq!(reloaded.offset_ms, 200);
+        assert_eq!(reloaded.lines, original.lines);
+    }
+
+    #[test]
+    fn test_save_lrc_keeps_unknown_tags() {
+        use flac_music_player::lyrics::LrcWriter;
+
+        let original = LrcParser::parse("[ti:曲名]\n[re:Editor]\n[ve:1.0]\n[la:ja]\n[#:コメント]\n[00:01.00]一行目\n");
+        assert_eq!(original.lines.len(), 1);
+        let saved = LrcWriter::to_lrc_string(&original);
+        assert_eq!(saved, "[ti:曲名]\n[re:Editor]\n[ve:1.0]\n[la:ja]\n[#:コメント]\n[00:01.00]一行目\n");
+        assert_eq!(LrcParser::parse(&saved), original);
+    }
+
+    #[test]
+    fn test_offset_write_back_keeps_other_lines() {
+        use flac_music_player::lyrics::LrcWriter;
+
+        let dir = TempDir::new("lyrics_offset_write_test");
+        let lrc_path = dir.join("song.lrc");
+        // 未知のタグ・コメント・タイムスタンプの無い行・複数タイムスタンプの行、Shift-JISの歌詞（"歌"）
+        let mut original = b"[ti:Title]\n[re:Editor]\n[ve:1.0]\n[la:ja]\n\n[#comment]\nCredits line\n".to_vec();
+        original.extend_from_slice(b"[00:10.005][01:20.00]\x89\xcc chorus\n[00:15.00]verse");
+        std::fs::write(&lrc_path, &original).unwrap();
+
+        LrcWriter::adjust_offset_in_file(&lrc_path, 250).unwrap();
+        let written = std::fs::read(&lrc_path).unwrap();
+        let mut expected = b"[ti:Title]\n[re:Editor]\n[ve:1.0]\n[la:ja]\n\n[#comment]\n[offset:+250]\nCredits line\n".to_vec();
+        expected.extend_from_slice(b"[00:10.005][01:20.00]\x89\xcc chorus\n[00:15.00]verse");
+        assert_eq!(written, expected);
+        assert!(!dir.join("song.lrc.tmp").exists());
+        assert_eq!(LyricsLoader::load_from_file(&lrc_path).unwrap().offset_ms, 250);
+
+        // 既存のタグは値だけを書き換える
+        LrcWriter::adjust_offset_in_file(&lrc_path, -400).unwrap();
+        let written = std::fs::read(&lrc_path).unwrap();
+        let mut expected = b"[ti:Title]\n[re:Editor]\n[ve:1.0]\n[la:ja]\n\n[#comment]\n[offset:-150]\nCredits line\n".to_vec();
+        expected.extend_from_slice(b"[00:10.005][01:20.00]\x89\xcc chorus\n[00:15.00]verse");
+        assert_eq!(written, expected);
+    }
+
+    #[test]
+    fn test_offset_tag_is_inserted_at_top_without_header_tags() {
+        use flac_music_player::lyrics::LrcWriter;
+
+        assert_eq!(LrcWriter::adjust_offset_tag(b"[00:01.00]a\r\n[00:02.00]b\r\n", -100),
+            b"[offset:-100]\r\n[00:01.00]a\r\n[00:02.00]b\r\n".to_vec());
+        assert_eq!(LrcWriter::adjust_offset_tag(b"\xEF\xBB\xBF[ti:t]", 100),
+            b"\xEF\xBB\xBF[ti:t]\n[offset:+100]\n".to_vec());
+        assert_eq!(LrcWriter::adjust_offset_tag(b"[OFFSET: +200 ]\r\n[00:01.00]a", 50),
+            b"[OFFSET:+250]\r\n[00:01.00]a".to_vec());
+    }
+
+    #[test]
+    fn test_lyrics_offset_manager_persistence() {
+        use flac_music_player::lyrics::LyricsOffsetManager;
+
+        let dir = TempDir::new("lyrics_offsets_test");
+        let offsets_file = dir.join("lyrics_offsets.json");
+        let track_path = PathBuf::from("/music/song.flac");
+
+        let mut manager = LyricsOffsetManager::with_file_path(offsets_file.clone());
+        assert_eq!(manager.get_offset(&track_path), 0);
+        assert_eq!(manager.adjust_offset(&track_path, 100), 100);
+        assert_eq!(manager.adjust_offset(&track_path, 200), 300);
+        manager.save_to_file().unwrap();
+
+        let mut reloaded = LyricsOffsetManager::with_file_path(offsets_file);
+        reloaded.load_from_file().unwrap();
+        assert_eq!(reloaded.get_offset(&track_path), 300);
+
+        // 0に戻した楽曲は保存対象から外れる
+        reloaded.adjust_offset(&track_path, -300);
+        assert_eq!(reloaded.get_offset(&track_path), 0);
+    }
+}
+
+#[cfg(test)]
+mod playback_rate_tests {
+    use super::TempDir;
+    use flac_music_player::player::PlaybackRateManager;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_playback_rate_manager_persistence() {
+        let dir = TempDir::new("playback_rates_test");
+        let rates_file = dir.join("playback_rates.json");
+        let track_path = PathBuf::from("/music/etude.flac");
+
+        let mut manager = PlaybackRateManager::with_file_path(rates_file.clone());
+        assert_eq!(manager.get_rate(&track_path), 1.0);
+        manager.set_rate(&track_path, 0.75);
+        manager.save_to_file().unwrap();
+
+        let mut reloaded = PlaybackRateManager::with_file_path(rates_file);
+        reloaded.load_from_file().unwrap();
+        assert_eq!(reloaded.get_rate(&track_path), 0.75);
+
+        // 等速に戻した楽曲は保存対象から外れる
+        reloaded.set_rate(&track_path, 1.0);
+        assert_eq!(reloaded.get_rate(&track_path), 1.0);
+    }
+}
+
+#[cfg(test)]
+mod position_tracker_tests {
+    use flac_music_player::player::position::PositionTracker;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_position_compensates_buffer_latency() {
+        let mut tracker = PositionTracker::new();
+        let start = Instant::now();
+
+        // エンジンの位置は10ms単位で更新される
+        assert_eq!(tracker.update(0.0, true, start), 0.0);
+        tracker.update(0.01, true, start + Duration::from_millis(10));
+        let position = tracker.update(0.02, true, start + Duration::from_millis(20));
+
+        // 1バッファ分の遅延を差し引く
+        assert!((position - 0.01).abs() < 1e-9);
+
+        // 更新の間は経過時間で補間するが、1バッファ分を超えない
+        let interpolated = tracker.update(0.02, true, start + Duration::from_millis(25));
+        assert!((interpolated - 0.015).abs() < 1e-9);
+        let capped = tracker.update(0.02, true, start + Duration::from_millis(100));
+        assert!((capped - 0.02).abs() < 1e-9);
+
+        // 一時停止中は補間しない
+        let paused = tracker.update(0.02, false, start + Duration::from_millis(200));
+        assert!((paused - 0.01).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_position_reports_seek_target_until_engine_catches_up() {
+        let mut tracker = PositionTracker::new();
+        let start = Instant::now();
+        tracker.update(5.0, true, start);
+
+        tracker.start_seek(60.0, start);
+        assert_eq!(tracker.update(5.0, true, start + Duration::from_millis(10)), 60.0);
+
+        // 反映後はシーク先より前に戻らない
+        let position = tracker.update(60.0, true, start + Duration::from_millis(20));
+        assert_eq!(position, 60.0);
+        assert!(tracker.update(60.01, true, start + Duration::from_millis(30)) >= 60.0);
+    }
+
+    #[test]
+    fn test_position_scales_with_playback_rate() {
+        let mut tracker = PositionTracker::new();
+        tracker.set_playback_rate(0.5);
+        let start = Instant::now();
+
+        // 半速ではエンジンの位置は10msごとに5msずつ進む
+        tracker.update(0.0, true, start);
+        tracker.update(0.005, true, start + Duration::from_millis(10));
+        let position = tracker.update(0.01, true, start + Duration::from_millis(20));
+        assert!((position - 0.005).abs() < 1e-9);
+
+        // 補間も実時間の経過に速度を掛けて進める
+        let interpolated = tracker.update(0.01, true, start + Duration::from_millis(26));
+        assert!((interpolated - 0.008).abs() < 1e-9);
+
+        // 新しい楽曲でも速度は引き継ぐ
+        tracker.reset();
+        tracker.update(0.0, true, start + Duration::from_millis(30));
+        tracker.update(0.005, true, start + Duration::from_millis(40));
+        let position = tracker.update(0.01, true, start + Duration::from_millis(50));
+        assert!((position - 0.005).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_position_follows_ab_loop_wrap() {
+        let mut tracker = PositionTracker::new();
+        let start = Instant::now();
+
+        // 60秒から再生を開始し、40〜61秒のA–B区間の先頭に折り返す
+        tracker.start_seek(60.0, start);
+        tracker.update(60.0, true, start + Duration::from_millis(10));
+        tracker.update(60.99, true, start + Duration::from_millis(20));
+        let wrapped = tracker.update(40.01, true, start + Duration::from_millis(30));
+
+        // シーク先より前でも折り返し後の位置を表示する
+        assert!((wrapped - 40.0).abs() < 0.02);
+    }
+}
+
+#[cfg(test)]
+mod audio_format_tests {
+    use super::TempDir;
+    use flac_music_player::music::format::{AudioFormat, is_supported_audio_file, is_unplayable_audio_file, read_duration, read_tags, read_text_tags};
+    use flac_music_player::music::metadata::get_track_metadata;
+    use std::path::Path;
+
+    /// LIST/INFOチャンク付きの1秒間の無音WAV（8kHz・16bit・モノラル）を生成
+    pub fn write_test_wav(path: &Path, title: &str, artist: &str) {
+        fn info_entry(id: &[u8; 4], value: &str) -> Vec<u8> {
+            let mut data = value.as_bytes().to_vec();
+            data.push(0);
+            if data.len() % 2 == 1 {
+                data.push(0);
+            }
+            let mut entry = id.to_vec();
+            entry.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            entry.extend_from_slice(&data);
+            entry
+        }
+
+        let sample_rate: u32 = 8000;
+        let samples = vec![0u8; sample_rate as usize * 2];
+
+        let mut fmt = Vec::new();
+        fmt.extend_from_slice(&1u16.to_le_bytes());               // PCM
+        fmt.extend_from_slice(&1u16.to_le_bytes());               // モノラル
+        fmt.extend_from_slice(&sample_rate.to_le_bytes());
+        fmt.extend_from_slice(&(sample_rate * 2).to_le_bytes());  // バイトレート
+        fmt.extend_from_slice(&2u16.to_le_bytes());               // ブロックサイズ
+        fmt.extend_from_slice(&16u16.to_le_bytes());              // ビット深度
+
+        let mut info = b"INFO".to_vec();
+        info.extend(info_entry(b"INAM", title));
+        info.extend(info_entry(b"IART", artist));
+
+        let mut body = b"WAVE".to_vec();
+        for (id, chunk) in [(b"fmt ", &fmt), (b"LIST", &info), (b"data", &samples)] {
+            body.extend_from_slice(id);
+            body.extend_from_slice(&(chunk.len() as u32).to_le_bytes());
+            body.extend_from_slice(chunk);
+        }
+
+        let mut wav = b"RIFF".to_vec();
+        wav.extend_from_slice(&(body.len() as u32).to_le_bytes());
+        wav.extend(body);
+        std::fs::write(path, wav).unwrap();
+    }
+
+    #[test]
+    fn test_audio_format_detection() {
+        assert_eq!(AudioFormat::from_path(Path::new("a.FLAC")), Some(AudioFormat::Flac));
+        assert_eq!(AudioFormat::from_path(Path::new("a.mp3")), Some(AudioFormat::Mp3));
+        assert_eq!(AudioFormat::from_path(Path::new("a.m4a")), Some(AudioFormat::M4a));
+        assert_eq!(AudioFormat::from_path(Path::new("a.m4b")), Some(AudioFormat::M4a));
+        // 動画を含み得る.mp4は取り込まない
+        assert_eq!(AudioFormat::from_path(Path::new("a.mp4")), None);
+        assert_eq!(AudioFormat::from_path(Path::new("a.aiff")), Some(AudioFormat::Aiff));
+        assert_eq!(AudioFormat::from_path(Path::new("a.txt")), None);
+
+        assert!(is_supported_audio_file(Path::new("a.ogg")));
+        assert!(is_supported_audio_file(Path::new("a.wav")));
+        // Opusはデコーダーがないためライブラリに取り込まない
+        assert!(!is_supported_audio_file(Path::new("a.opus")));
+        assert!(is_unplayable_audio_file(Path::new("a.opus")));
+        assert!(!is_unplayable_audio_file(Path::new("a.flac")));
+    }
+
+    #[test]
+    fn test_wav_metadata_and_duration() {
+        let dir = TempDir::new("audio_format_test");
+        let path = dir.join("test.wav");
+        write_test_wav(&path, "Test Title", "Test Artist");
+
+        let track = get_track_metadata(&path).unwrap();
+        assert_eq!(track.title, "Test Title");
+        assert_eq!(track.artist, "Test Artist");
+        assert_eq!(track.album, "Unknown Album");
+
+        let duration = read_duration(&path).unwrap();
+        assert!((duration.as_secs_f64() - 1.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_flac_text_tags_skip_pictures() {
+        let dir = TempDir::new("audio_format_flac_test");
+        let path = dir.join("test.flac");
+        let mut flac = b"fLaC".to_vec();
+        flac.extend_from_slice(&[0x80, 0, 0, 34]); // 最後のメタデータブロック: STREAMINFO
+        let mut stream_info = [0u8; 34];
+        // 44.1kHz・ステレオ・16bit・1秒
+        stream_info[10..14].copy_from_slice(&[0x0A, 0xC4, 0x42, 0xF0]);
+        stream_info[14..18].copy_from_slice(&44100u32.to_be_bytes());
+        flac.extend_from_slice(&stream_info);
+        std::fs::write(&path, flac).unwrap();
+
+        let mut tag = metaflac::Tag::read_from_path(&path).unwrap();
+        tag.add_picture("image/png", metaflac::block::PictureType::CoverFront, vec![0; 1024]);
+        tag.set_vorbis("REPLAYGAIN_TRACK_GAIN", vec!["-6.50 dB"]);
+        tag.save().unwrap();
+
+        let tags = read_text_tags(&path).unwrap();
+        assert_eq!(tags.get("REPLAYGAIN_TRACK_GAIN"), Some("-6.50 dB"));
+        assert!(tags.pictures.is_empty());
+        assert!((tags.duration.unwrap().as_secs_f64() - 1.0).abs() < 0.01);
+        assert!((read_duration(&path).unwrap().as_secs_f64() - 1.0).abs() < 0.01);
+        assert_eq!(read_tags(&path).unwrap().pictures.len(), 1);
+    }
+}
+
+#[cfg(test)]
+mod library_index_tests {
+    use super::TempDir;
+    use super::audio_format_tests::write_test_wav;
+    use flac_music_player::music::MusicLibrary;
+    use flac_music_player::music::library_index::LibraryIndex;
+    use flac_music_player::music::library_roots::ScanRoot;
+    use flac_music_player::music::library_scanner::{LibraryScanner, LibraryScanResult};
+    use flac_music_player::music::replaygain::ReplayGain;
+    use std::collections::HashMap;
+    use std::fs::File;
+    use std::path::Path;
+    use std::time::{Duration, SystemTime};
+
+    fn scan_titles(music_dir: &Path, index_file: &Path) -> Vec<String> {
+        let mut index = LibraryIndex::with_file_path(index_file.to_path_buf());
+        index.load_from_file().unwrap();
+        let mut library = MusicLibrary::with_index(false, index);
+        library.scan_directory(music_dir);
+        let mut titles: Vec<String> = library.get_tracks().iter().map(|track| track.title.clone()).collect();
+        titles.sort();
+        titles
+    }
+
+    fn set_modified(path: &Path, modified: SystemTime) {
+        File::options().write(true).open(path).unwrap().set_modified(modified).unwrap();
+    }
+
+    #[test]
+    fn test_rescan_rereads_only_changed_files() {
+        let dir = TempDir::new("library_index_test");
+        let music_dir = dir.join("music");
+        std::fs::create_dir_all(&music_dir).unwrap();
+        let index_file = dir.join("library_index.json");
+        let modified = SystemTime::now() - Duration::from_secs(3600);
+
+        let first = music_dir.join("first.wav");
+        let second = music_dir.join("second.wav");
+        write_test_wav(&first, "Old Title", "Artist");
+        write_test_wav(&second, "Second", "Artist");
+        set_modified(&first, modified);
+        assert_eq!(scan_titles(&music_dir, &index_file), vec!["Old Title", "Second"]);
+        assert!(index_file.exists());
+        assert!(!dir.join("library_index.json.tmp").exists());
+
+        // サイズと更新日時が同じファイルはタグを読み直さない
+        write_test_wav(&first, "New Title", "Artist");
+        set_modified(&first, modified);
+        assert_eq!(scan_titles(&music_dir, &index_file), vec!["Old Title", "Second"]);
+
+        // 更新日時が変わったファイルは読み直し、削除されたファイルは除かれる
+        set_modified(&first, modified + Duration::from_secs(60));
+        std::fs::remove_file(&second).unwrap();
+        assert_eq!(scan_titles(&music_dir, &index_file), vec!["New Title"]);
+    }
+
+    #[test]
+    fn test_replay_gain_is_kept_in_index() {
+        let dir = TempDir::new("library_index_replay_gain_test");
+        let music_dir = dir.join("music");
+        std::fs::create_dir_all(&music_dir).unwrap();
+        let index_file = dir.join("library_index.json");
+        let track = music_dir.join("track.wav");
+        write_test_wav(&track, "Title", "Artist");
+
+        let mut index = LibraryIndex::with_file_path(index_file.clone());
+        index.load_from_file().unwrap();
+        let mut library = MusicLibrary::with_index(false, index);
+        library.scan_directory(&music_dir);
+        let gain = ReplayGain { track_gain_db: Some(-6.5), track_peak: Some(0.9), ..Default::default() };
+        library.update_replay_gain(&HashMap::from([(track.clone(), gain)]));
+        library.save_index();
+
+        // タグに書き込まなくても、次回のスキャンで解析結果が読み込まれる
+        let mut index = LibraryIndex::with_file_path(index_file);
+        index.load_from_file().unwrap();
+        let mut library = MusicLibrary::with_index(false, index);
+        library.scan_directory(&music_dir);
+        assert_eq!(library.get_tracks()[0].replay_gain, Some(gain));
+    }
+
+    fn wait_for_scan(scanner: &mut LibraryScanner) -> LibraryScanResult {
+        let started_at = std::time::Instant::now();
+        loop {
+            if let Some(result) = scanner.poll() {
+                return result;
+            }
+            assert!(started_at.elapsed() < Duration::from_secs(10), "library scan did not finish");
+            std::thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    #[test]
+    fn test_background_scan_streams_tracks_and_returns_index() {
+        let dir = TempDir::new("library_scanner_test");
+        let album_dir = dir.join("music").join("album");
+        std::fs::create_dir_all(&album_dir).unwrap();
+        let index_file = dir.join("library_index.json");
+        write_test_wav(&album_dir.join("first.wav"), "First", "Artist");
+        write_test_wav(&album_dir.join("second.wav"), "Second", "Artist");
+        std::fs::write(album_dir.join("third.opus"), b"OggS").unwrap();
+
+        let mut scanner = LibraryScanner::new();
+        scanner.start(vec![ScanRoot::new(dir.join("music"))], Some(LibraryIndex::with_file_path(index_file.clone())));
+        assert!(scanner.is_running());
+        let result = wait_for_scan(&mut scanner);
+
+        let progress = scanner.get_progress();
+        assert!(!scanner.is_running());
+        assert!(progress.finished);
+        assert_eq!(progress.total, Some(2));
+        assert_eq!(progress.completed, 2);
+        // 再生できない形式のファイルは取り込まずに報告する
+        assert_eq!(progress.unplayable_files, vec![album_dir.join("third.opus")]);
+
+        let mut titles: Vec<String> = result.tracks.unwrap().into_iter().map(|track| track.title).collect();
+        titles.sort();
+        assert_eq!(titles, vec!["First", "Second"]);
+
+        // スキャンスレッドに渡したインデックスは保存されて戻り、次回のスキャンで使える
+        assert!(index_file.exists());
+        let mut library = MusicLibrary::new(false);
+        library.set_index(result.index.unwrap());
+        library.scan_directory(&dir.join("music"));
+        assert_eq!(library.get_track_count(), 2);
+    }
+
+    #[test]
+    fn test_cancelled_background_scan_returns_no_tracks() {
+        let dir = TempDir::new("library_scanner_cancel_test");
+        write_test_wav(&dir.join("track.wav"), "Track", "Artist");
+
+        let mut scanner = LibraryScanner::new();
+        scanner.start(vec![ScanRoot::new(dir.path())], Some(LibraryIndex::with_file_path(dir.join("library_index.json"))));
+        scanner.cancel();
+        let result = wait_for_scan(&mut scanner);
+
+        assert!(scanner.get_progress().cancelled);
+        assert!(result.tracks.is_none());
+        // 中止してもインデックスは呼び出し側に戻る
+        assert!(result.index.is_some());
+    }
+}
+
+#[cfg(test)]
+mod library_watcher_tests {
+    use super::TempDir;
+    use super::audio_format_tests::write_test_wav;
+    use flac_music_player::music::MusicLibrary;
+    use flac_music_player::music::library::read_changed_tracks;
+    use flac_music_player::music::library_index::LibraryIndex;
+    use flac_music_player::music::library_scanner::LibraryScanner;
+    use flac_music_player::music::library_roots::ScanRoot;
+    use flac_music_player::music::library_watcher::PendingChanges;
+    use std::path::PathBuf;
+    use std::time::{Duration, Instant};
+
+    fn titles(library: &MusicLibrary) -> Vec<String> {
+        let mut titles: Vec<String> = library.get_tracks().iter().map(|track| track.title.clone()).collect();
+        titles.sort();
+        titles
+    }
+
+    #[test]
+    fn test_pending_changes_are_coalesced_until_quiet() {
+        let debounce = Duration::from_millis(1500);
+        let start = Instant::now();
+        let mut pending = PendingChanges::new();
+        assert!(pending.take_settled(start, debounce).is_none());
+
+        pending.record(vec![PathBuf::from("/music/b.flac"), PathBuf::from("/music/a.flac")], start);
+        pending.record(vec![PathBuf::from("/music/a.flac")], start + Duration::from_secs(1));
+
+        // 最後の変更から待ち時間が経つまでは反映しない
+        assert!(pending.take_settled(start + Duration::from_secs(2), debounce).is_none());
+
+        let changes = pending.take_settled(start + Duration::from_secs(3), debounce).unwrap();
+        assert_eq!(changes.paths, vec![PathBuf::from("/music/a.flac"), PathBuf::from("/music/b.flac")]);
+        assert!(!changes.rescan);
+        assert!(pending.take_settled(start + Duration::from_secs(10), debounce).is_none());
+
+        pending.record_rescan(start);
+        assert!(pending.take_settled(start + Duration::from_secs(2), debounce).unwrap().rescan);
+    }
+
+    #[test]
+    fn test_apply_changes_updates_only_changed_tracks() {
+        let dir = TempDir::new("library_watcher_test");
+        let album_dir = dir.join("album");
+        std::fs::create_dir_all(&album_dir).unwrap();
+        let kept = album_dir.join("kept.wav");
+        let retagged = album_dir.join("retagged.wav");
+        let removed = dir.join("removed.wav");
+        write_test_wav(&kept, "Kept", "Artist");
+        write_test_wav(&retagged, "Old", "Artist");
+        write_test_wav(&removed, "Removed", "Artist");
+
+        let mut library = MusicLibrary::new(false);
+        library.scan_directory(&dir);
+        assert_eq!(titles(&library), vec!["Kept", "Old", "Removed"]);
+        let roots = [ScanRoot::new(dir.path())];
+
+        let added = dir.join("added.wav");
+        write_test_wav(&added, "Added", "Artist");
+        write_test_wav(&retagged, "New", "Artist");
+        std::fs::remove_file(&removed).unwrap();
+        let update = library.apply_changes(read_changed_tracks(&roots, &[added.clone(), retagged.clone(), removed.clone()], None));
+
+        assert_eq!(update.added.len(), 1);
+        assert_eq!(update.added[0].path, added);
+        assert_eq!(update.updated, 1);
+        assert_eq!(update.removed, 1);
+        assert_eq!(titles(&library), vec!["Added", "Kept", "New"]);
+
+        // ディレクトリの名前変更は削除と追加として反映する
+        let renamed_dir = dir.join("renamed");
+        std::fs::rename(&album_dir, &renamed_dir).unwrap();
+        let update = library.apply_changes(read_changed_tracks(&roots, &[album_dir.clone(), renamed_dir.clone()], None));
+        assert_eq!(update.removed, 2);
+        assert_eq!(update.added.len(), 2);
+        assert!(library.get_tracks().iter().all(|track| !track.path.starts_with(&album_dir)));
+        assert_eq!(titles(&library), vec!["Added", "Kept", "New"]);
+
+        // 対応形式以外のファイルの変更ではツリーを作り直さない
+        let cover = dir.join("cover.jpg");
+        std::fs::write(&cover, b"not audio").unwrap();
+        assert!(library.apply_changes(read_changed_tracks(&roots, &[cover], None)).is_empty());
+    }
+
+    #[test]
+    fn test_changes_are_read_on_scan_thread() {
+        let dir = TempDir::new("library_watcher_scanner_test");
+        let index_file = dir.join("library_index.json");
+        let music = dir.join("music");
+        std::fs::create_dir_all(&music).unwrap();
+        let removed = music.join("removed.wav");
+        write_test_wav(&removed, "Removed", "Artist");
+
+        let mut library = MusicLibrary::with_index(false, LibraryIndex::with_file_path(index_file.clone()));
+        library.scan_directory(&music);
+        assert_eq!(titles(&library), vec!["Removed"]);
+
+        let added = music.join("added.wav");
+        write_test_wav(&added, "Added", "Artist");
+        std::fs::remove_file(&removed).unwrap();
+        let mut scanner = LibraryScanner::new();
+        scanner.start_changes(vec![ScanRoot::new(&music)], vec![added.clone(), removed.clone()], library.take_index());
+        assert!(scanner.get_progress().changes_only);
+
+        let started_at = Instant::now();
+        let result = loop {
+            if let Some(result) = scanner.poll() {
+                break result;
+            }
+            assert!(started_at.elapsed() < Duration::from_secs(10), "library scan did not finish");
+            std::thread::sleep(Duration::from_millis(5));
+        };
+        assert!(result.tracks.is_none());
+        library.set_index(result.index.unwrap());
+        let update = library.apply_changes(result.changes.unwrap());
+        assert_eq!(update.added.len(), 1);
+        assert_eq!(update.removed, 1);
+        assert_eq!(titles(&library), vec!["Added"]);
+
+        // 削除されたファイルはインデックスからも取り除いて保存する
+        let saved_index = std::fs::read_to_string(&index_file).unwrap();
+        assert!(saved_index.contains("added.wav"));
+        assert!(!saved_index.contains("removed.wav"));
+    }
+
+    #[test]
+    fn test_apply_changes_ignores_excluded_paths() {
+        let dir = TempDir::new("library_watcher_exclude_test");
+        let incoming_dir = dir.join("_incoming");
+        std::fs::create_dir_all(&incoming_dir).unwrap();
+        let mut root = ScanRoot::new(dir.path());
+        root.add_exclude("**/_incoming/**").unwrap();
+
+        let mut library = MusicLibrary::new(false);
+        library.scan_roots(std::slice::from_ref(&root));
+        let incoming = incoming_dir.join("copying.wav");
+        write_test_wav(&incoming, "Copying", "Artist");
+        assert!(library.apply_changes(read_changed_tracks(std::slice::from_ref(&root), std::slice::from_ref(&incoming), None)).is_empty());
+
+        // 除外フォルダから移動されたファイルは追加する
+        let moved = dir.join("moved.wav");
+        std::fs::rename(&incoming, &moved).unwrap();
+        let update = library.apply_changes(read_changed_tracks(std::slice::from_ref(&root), &[incoming, moved], None));
+        assert_eq!(update.added.len(), 1);
+        assert_eq!(titles(&library), vec!["Copying"]);
+    }
+}
+
+#[cfg(test)]
+mod library_roots_tests {
+    use super::TempDir;
+    use super::audio_format_tests::write_test_wav;
+    use flac_music_player::music::MusicLibrary;
+    use flac_music_player::music::library_roots::ScanRoot;
+    
+    fn scan_titles(roots: &[ScanRoot]) -> Vec<String> {
+        let mut library = MusicLibrary::new(false);
+        library.scan_roots(roots);
+        let mut titles: Vec<String> = library.get_tracks().iter().map(|track| track.title.clone()).collect();
+        titles.sort();
+        titles
+    }
+
+    #[test]
+    fn test_scan_multiple_roots_with_exclude_patterns() {
+        let dir = TempDir::new("library_roots_test");
+        let first = dir.join("drive1");
+        let second = dir.join("nas");
+        std::fs::create_dir_all(first.join("album").join("_incoming")).unwrap();
+        std::fs::create_dir_all(second.join("_incoming")).unwrap();
+        write_test_wav(&first.join("album").join("a.wav"), "A", "Artist");
+        write_test_wav(&first.join("album").join("_incoming").join("partial.wav"), "Partial", "Artist");
+        write_test_wav(&first.join("album").join("b.tmp.wav"), "Temp", "Artist");
+        write_test_wav(&second.join("c.wav"), "C", "Artist");
+        write_test_wav(&second.join("_incoming").join("d.wav"), "D", "Artist");
+
+        let mut first_root = ScanRoot::new(&first);
+        first_root.add_exclude("**/_incoming/**").unwrap();
+        first_root.add_exclude("**/*.tmp.wav").unwrap();
+        first_root.add_exclude("  ").unwrap();
+        assert!(first_root.add_exclude("[").is_err());
+        let second_root = ScanRoot::new(&second);
+
+        // 除外パターンはそれを設定したフォルダにのみ適用する
+        assert_eq!(scan_titles(&[first_root.clone(), second_root]), vec!["A", "C", "D"]);
+        assert!(!first_root.includes(&first.join("album").join("_incoming").join("new.wav")));
+        assert!(first_root.includes(&first.join("album").join("new.wav")));
+        assert!(!first_root.includes(&second.join("c.wav")));
+
+        // 存在しないフォルダは無視する
+        assert_eq!(scan_titles(&[first_root, ScanRoot::new(dir.join("offline"))]), vec!["A"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlinks_are_followed_only_when_enabled_and_loops_are_skipped() {
+        let dir = TempDir::new("library_roots_symlink_test");
+        let music = dir.join("music");
+        let elsewhere = dir.join("elsewhere");
+        std::fs::create_dir_all(music.join("album")).unwrap();
+        std::fs::create_dir_all(&elsewhere).unwrap();
+        write_test_wav(&music.join("album").join("a.wav"), "A", "Artist");
+        write_test_wav(&elsewhere.join("b.wav"), "B", "Artist");
+        std::os::unix::fs::symlink(&elsewhere, music.join("linked")).unwrap();
+        // 親ディレクトリへのリンクによるループ
+        std::os::unix::fs::symlink(&music, music.join("album").join("loop")).unwrap();
+
+        assert_eq!(scan_titles(&[ScanRoot::new(&music)]), vec!["A"]);
+        let following = ScanRoot::new(&music).with_follow_symlinks(true);
+        assert_eq!(scan_titles(&[following]), vec!["A", "B"]);
+
+        // 重なったフォルダの楽曲は一度だけ読み込む
+        let overlapping = [ScanRoot::new(&music), ScanRoot::new(music.join("album"))];
+        assert_eq!(scan_titles(&overlapping), vec!["A"]);
+    }
+}
+
+#[cfg(test)]
+mod playlist_tests {
+    use super::TempDir;
+    use flac_music_player::music::TrackInfo;
+    use flac_music_player::playlist::PlaylistManager;
+    use flac_music_player::settings::RepeatMode;
+    use std::path::PathBuf;
+
+    pub fn create_track(name: &str) -> TrackInfo {
+        TrackInfo {
+            title: name.to_string(),
+            artist: "Test Artist".to_string(),
+            album_artist: None,
+            album: "Test Album".to_string(),
+            composer: None,
+            genre: None,
+            track_number: None,
+            track_total: None,
+            disc_number: None,
+            disc_total: None,
+            date: None,
+            path: PathBuf::from(format!("/test/{}.flac", name)),
+            replay_gain: None,
+        }
+    }
+
+    #[test]
+    fn test_peek_next_does_not_move_current_track() {
+        let mut manager = PlaylistManager::new();
+        manager.add_track(create_track("a"));
+        manager.add_track(create_track("b"));
+        manager.set_current_playing_index(Some(0));
+
+        let peeked = manager.peek_next_with_modes(&RepeatMode::Normal, false);
+        assert_eq!(peeked.map(|track| track.title), Some("b".to_string()));
+        assert_eq!(manager.get_current_playing_index(), Some(0));
+
+        // 先読みした楽曲と実際に進んだ楽曲が一致する
+        let next = manager.move_to_next_with_modes(&RepeatMode::Normal, false);
+        assert_eq!(next.map(|track| track.title), Some("b".to_string()));
+
+        assert!(manager.peek_next_with_modes(&RepeatMode::Normal, false).is_none());
+        let wrapped = manager.peek_next_with_modes(&RepeatMode::RepeatAll, false);
+        assert_eq!(wrapped.map(|track| track.title), Some("a".to_string()));
+        assert_eq!(manager.get_current_playing_index(), Some(1));
+    }
+
+    #[test]
+    fn test_peek_next_matches_move_to_next_in_every_mode() {
+        for shuffle_enabled in [false, true] {
+            for repeat_mode in [RepeatMode::Normal, RepeatMode::RepeatAll, RepeatMode::RepeatOne] {
+                let mut manager = PlaylistManager::new();
+                for name in ["a", "b", "c"] {
+                    manager.add_track(create_track(name));
+                }
+                manager.set_current_playing_index(Some(0));
+                manager.update_shuffle_when_settings_changed(shuffle_enabled);
+
+                for _ in 0..4 {
+                    let peeked = manager.peek_next_with_modes(&repeat_mode, shuffle_enabled);
+                    let next = manager.move_to_next_with_modes(&repeat_mode, shuffle_enabled);
+                    // 順序を作り直す場合のみ先読みできない
+                    if peeked.is_some() {
+                        assert_eq!(peeked.map(|track| track.path), next.map(|track| track.path));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_playing_position_and_shuffle_order_persist() {
+        let dir = TempDir::new("playlist_session_test");
+        let playlists_file = dir.join("playlists.json");
+
+        // 読み込み時に存在しない楽曲は削除されるため、実在するファイルを用意する
+        let mut manager = PlaylistManager::new();
+        for name in ["a", "b", "c"] {
+            let mut track = create_track(name);
+            track.path = dir.join(format!("{}.flac", name));
+            std::fs::write(&track.path, b"").unwrap();
+            manager.add_track(track);
+        }
+        manager.set_current_playing_index(Some(1));
+        manager.update_shuffle_when_settings_changed(true);
+        // シャッフル順序の最後の楽曲では次に進む時に順序が作り直されるため、順序の途中から始める
+        while manager.peek_next_with_modes(&RepeatMode::Normal, true).is_none() {
+            manager.move_to_next_with_modes(&RepeatMode::RepeatAll, true);
+        }
+        let expected_current = manager.get_current_track().map(|track| track.title.clone());
+        let expected_next = manager.peek_next_with_modes(&RepeatMode::RepeatAll, true);
+        assert!(expected_next.is_some());
+        manager.save_to_file(&playlists_file).unwrap();
+
+        let reloaded = PlaylistManager::load_from_file(&playlists_file).unwrap();
+        assert_eq!(reloaded.get_current_playing_playlist_id(), Some("default"));
+        assert_eq!(reloaded.get_current_track().map(|track| track.title.clone()), expected_current);
+        // 保存したシャッフル順序のまま次の楽曲に進む
+        let next = reloaded.peek_next_with_modes(&RepeatMode::RepeatAll, true);
+        assert_eq!(next.map(|track| track.title), expected_next.map(|track| track.title));
+    }
+
+    #[test]
+    fn test_playlist_file_with_inline_cover_art_is_rewritten_without_it() {
+        let dir = TempDir::new("playlist_cover_art_test");
+        let playlists_file = dir.join("playlists.json");
+        let mut track = create_track("a");
+        track.path = dir.join("a.flac");
+        std::fs::write(&track.path, b"").unwrap();
+
+        // 以前の形式（楽曲ごとに画像データを保存）のファイルを用意する
+        let mut manager = PlaylistManager::new();
+        manager.add_track(track);
+        manager.save_to_file(&playlists_file).unwrap();
+        let mut json: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&playlists_file).unwrap()).unwrap();
+        json["playlists"][0]["tracks"][0]["cover_art"] = serde_json::json!([137, 80, 78, 71]);
+        std::fs::write(&playlists_file, serde_json::to_string_pretty(&json).unwrap()).unwrap();
+
+        let reloaded = PlaylistManager::load_from_file(&playlists_file).unwrap();
+        assert_eq!(reloaded.get_active_track_count(), 1);
+        assert!(!std::fs::read_to_string(&playlists_file).unwrap().contains("\"cover_art\""));
+    }
+}
+
+#[cfg(test)]
+mod cover_art_tests {
+    use super::TempDir;
+    use super::audio_format_tests::write_test_wav;
+    use flac_music_player::app::CoverArtCache;
+    use flac_music_player::music::cover_art::{default_folder_image_names, find_folder_image};
+    use std::path::Path;
+
+    fn write_test_image(path: &Path, color: [u8; 3]) {
+        image::RgbImage::from_pixel(4, 4, image::Rgb(color)).save(path).unwrap();
+    }
+
+    /// 音声フレームを持たず、指定色の画像を埋め込んだFLACを生成
+    fn write_test_flac_with_picture(path: &Path, color: [u8; 3], size: u32) {
+        let mut flac = b"fLaC".to_vec();
+        flac.extend_from_slice(&[0x80, 0, 0, 34]); // 最後のメタデータブロック: STREAMINFO
+        let mut stream_info = [0u8; 34];
+        stream_info[0..2].copy_from_slice(&4096u16.to_be_bytes());
+        stream_info[2..4].copy_from_slice(&4096u16.to_be_bytes());
+        // 44.1kHz・ステレオ・16bit
+        stream_info[10..13].copy_from_slice(&[0x0A, 0xC4, 0x42]);
+        stream_info[13] = 0xF0;
+        flac.extend_from_slice(&stream_info);
+        std::fs::write(path, flac).unwrap();
+
+        let image = image::RgbImage::from_pixel(size, size, image::Rgb(color));
+        let mut png = std::io::Cursor::new(Vec::new());
+        image.write_to(&mut png, image::ImageOutputFormat::Png).unwrap();
+        let mut tag = metaflac::Tag::read_from_path(path).unwrap();
+        tag.add_picture("image/png", metaflac::block::PictureType::CoverFront, png.into_inner());
+        tag.save().unwrap();
+    }
+
+    #[test]
+    fn test_cover_art_is_loaded_on_demand_and_shared_between_tracks() {
+        let dir = TempDir::new("cover_art_test");
+        let first = dir.join("01.flac");
+        let second = dir.join("02.flac");
+        let other = dir.join("other.flac");
+        write_test_flac_with_picture(&first, [200, 0, 0], 600);
+        write_test_flac_with_picture(&second, [200, 0, 0], 600);
+        write_test_flac_with_picture(&other, [0, 0, 200], 600);
+
+        let ctx = egui::Context::default();
+        let mut cache = CoverArtCache::new();
+        assert_eq!(cache.art_id(&first), cache.art_id(&second));
+        assert_ne!(cache.art_id(&first), cache.art_id(&other));
+        assert!(cache.art_id(&dir.join("missing.flac")).is_none());
+        assert_eq!(cache.thumbnail_count(), 0);
+
+        // 表示用に縮小したサムネイルを、同じ画像の楽曲で共有する
+        let texture = cache.get_thumbnail(&ctx, &first).unwrap();
+        assert_eq!(texture.size(), [256, 256]);
+        assert_eq!(cache.get_thumbnail(&ctx, &second).unwrap().id(), texture.id());
+        assert_eq!(cache.thumbnail_count(), 1);
+    }
+
+    #[test]
+    fn test_art_ids_for_multiple_tracks_are_loaded_across_calls() {
+        let dir = TempDir::new("cover_art_multiple_test");
+        let same: Vec<_> = (0..3)
+            .map(|i| {
+                let path = dir.join(format!("{:02}.flac", i));
+                write_test_flac_with_picture(&path, [200, 0, 0], 4);
+                path
+            })
+            .collect();
+        let other = dir.join("other.flac");
+        write_test_flac_with_picture(&other, [0, 0, 200], 4);
+
+        // 1回に読み込む数を超える楽曲は、次の呼び出しで続きを読み込む
+        let mut cache = CoverArtCache::new();
+        let paths = || same.iter().map(|path| path.as_path());
+        assert!(cache.art_ids(paths(), 2).is_none());
+        let ids = cache.art_ids(paths(), 2).unwrap();
+        assert_eq!(ids.len(), 3);
+        assert!(ids.iter().all(|id| id.is_some() && *id == ids[0]));
+
+        // 画像の無い楽曲も読み込み済みとして記録する
+        let mut cache = CoverArtCache::new();
+        let missing = dir.join("missing.flac");
+        let paths = [other.as_path(), same[0].as_path(), missing.as_path()];
+        let ids = cache.art_ids(paths, 3).unwrap();
+        assert_ne!(ids[0], ids[1]);
+        assert!(ids[2].is_none());
+    }
+
+    #[test]
+    fn test_least_recently_used_thumbnails_are_evicted() {
+        let dir = TempDir::new("cover_art_lru_test");
+        let paths: Vec<_> = (0..65u8)
+            .map(|i| {
+                let path = dir.join(format!("{:02}.flac", i));
+                write_test_flac_with_picture(&path, [i, 255 - i, 0], 4);
+                path
+            })
+            .collect();
+
+        let ctx = egui::Context::default();
+        let mut cache = CoverArtCache::new();
+        let first = cache.get_thumbnail(&ctx, &paths[0]).unwrap();
+        for path in &paths[1..64] {
+            let _ = cache.get_thumbnail(&ctx, path).unwrap();
+        }
+        // 最初の画像を使い直すと、次に古い画像が破棄される
+        assert_eq!(cache.get_thumbnail(&ctx, &paths[0]).unwrap().id(), first.id());
+        let _ = cache.get_thumbnail(&ctx, &paths[64]).unwrap();
+        assert_eq!(cache.thumbnail_count(), 64);
+        assert_eq!(cache.get_thumbnail(&ctx, &paths[0]).unwrap().id(), first.id());
+    }
+
+    #[test]
+    fn test_folder_image_is_found_by_name_priority() {
+        let dir = TempDir::new("folder_image_test");
+        let names = default_folder_image_names();
+        assert_eq!(find_folder_image(&dir, &names), None);
+
+        write_test_image(&dir.join("AlbumArtSmall.jpg"), [0, 0, 200]);
+        std::fs::write(dir.join("cover.txt"), "not an image").unwrap();
+        assert_eq!(find_folder_image(&dir, &names), Some(dir.join("AlbumArtSmall.jpg")));
+
+        write_test_image(&dir.join("Folder.png"), [0, 200, 0]);
+        assert_eq!(find_folder_image(&dir, &names), Some(dir.join("Folder.png")));
+
+        write_test_image(&dir.join("cover.jpg"), [200, 0, 0]);
+        assert_eq!(find_folder_image(&dir, &names), Some(dir.join("cover.jpg")));
+
+        // 設定で優先順を変更できる
+        let custom = vec![" front ".to_string(), "".to_string(), "folder".to_string()];
+        assert_eq!(find_folder_image(&dir, &custom), Some(dir.join("Folder.png")));
+        assert_eq!(find_folder_image(&dir, &[]), None);
+    }
+
+    #[test]
+    fn test_folder_image_is_used_when_no_art_is_embedded() {
+        let dir = TempDir::new("folder_image_fallback_test");
+        let first = dir.join("01.wav");
+        let second = dir.join("02.wav");
+        let embedded = dir.join("03.flac");
+        write_test_wav(&first, "First", "Artist");
+        write_test_wav(&second, "Second", "Artist");
+        write_test_flac_with_picture(&embedded, [0, 0, 200], 4);
+
+        let ctx = egui::Context::default();
+        let mut cache = CoverArtCache::new();
+        assert!(cache.art_id(&first).is_none());
+
+        // フォルダ内の画像を同じフォルダの楽曲で共有し、埋め込み画像があればそちらを優先する
+        write_test_image(&dir.join("cover.jpg"), [200, 0, 0]);
+        cache.invalidate(&dir.join("cover.jpg"));
+        assert!(cache.art_id(&first).is_some());
+        assert_eq!(cache.art_id(&first), cache.art_id(&second));
+        assert_ne!(cache.art_id(&first), cache.art_id(&embedded));
+        let texture = cache.get_thumbnail(&ctx, &first).unwrap();
+        assert_eq!(cache.get_thumbnail(&ctx, &second).unwrap().id(), texture.id());
+
+        // 探す名前を変更すると読み直す
+        cache.set_folder_image_names(vec!["folder".to_string()]);
+        assert!(cache.art_id(&first).is_none());
+    }
+}
+
+#[cfg(test)]
+mod settings_tests {
+    use flac_music_player::settings::{CrossfadeCurve, LibraryRoot, Settings};
+
+    #[test]
+    fn test_single_target_directory_is_migrated_to_library_roots() {
+        let mut json = serde_json::to_value(Settings::default()).unwrap();
+        let object = json.as_object_mut().unwrap();
+        object.remove("library_roots");
+        object.insert("target_directory".to_string(), serde_json::json!("D:\\Music"));
+
+        let mut settings: Settings = serde_json::from_value(json).unwrap();
+        settings.migrate_target_directory();
+        assert_eq!(settings.library_roots, vec![LibraryRoot {
+            path: "D:\\Music".to_string(),
+            exclude_patterns: Vec::new(),
+            follow_symlinks: true,
+        }]);
+
+        // 移行後は旧形式の項目を保存しない
+        let saved = serde_json::to_value(&settings).unwrap();
+        assert!(saved.get("target_directory").is_none());
+        let mut reloaded: Settings = serde_json::from_value(saved).unwrap();
+        reloaded.migrate_target_directory();
+        assert_eq!(reloaded.library_roots, settings.library_roots);
+    }
+
+    #[test]
+    fn test_settings_without_crossfade_fields_keep_other_values() {
+        let mut json = serde_json::to_value(Settings::default()).unwrap();
+        let object = json.as_object_mut().unwrap();
+        object.remove("crossfade_seconds");
+        object.remove("crossfade_curve");
+        object.insert("seek_seconds".to_string(), serde_json::json!(25));
+
+        let settings: Settings = serde_json::from_value(json).unwrap();
+        assert_eq!(settings.seek_seconds, 25);
+        assert_eq!(settings.crossfade_seconds, 0.0);
+        assert_eq!(settings.crossfade_curve, CrossfadeCurve::Linear);
+    }
+
+    #[test]
+    fn test_crossfade_duration_is_clamped() {
+        let mut settings = Settings::default();
+        settings.set_crossfade_seconds(30.0);
+        assert_eq!(settings.get_crossfade_duration().as_secs(), 10);
+
+        settings.crossfade_seconds = f32::NAN;
+        assert!(settings.get_crossfade_duration().is_zero());
+    }
+
+    #[test]
+    fn test_volume_defaults_and_is_clamped() {
+        let mut json = serde_json::to_value(Settings::default()).unwrap();
+        let object = json.as_object_mut().unwrap();
+        object.remove("volume");
+        object.remove("muted");
+
+        let mut settings: Settings = serde_json::from_value(json).unwrap();
+        assert_eq!(settings.get_volume(), 1.0);
+        assert!(!settings.muted);
+
+        settings.set_volume(1.5);
+        assert_eq!(settings.get_volume(), 1.0);
+        settings.set_volume(-0.2);
+        assert_eq!(settings.get_volume(), 0.0);
+        settings.set_volume(0.35);
+        assert_eq!(settings.get_volume(), 0.35);
+    }
+
+    #[test]
+    fn test_output_device_defaults_to_system_default() {
+        let mut json = serde_json::to_value(Settings::default()).unwrap();
+        json.as_object_mut().unwrap().remove("output_device");
+
+        let settings: Settings = serde_json::from_value(json).unwrap();
+        assert_eq!(settings.output_device, None);
+
+        let mut settings = Settings::default();
+        settings.output_device = Some("USB Audio".to_string());
+        let json = serde_json::to_string(&settings).unwrap();
+        let reloaded: Settings = serde_json::from_str(&json).unwrap();
+        assert_eq!(reloaded.output_device.as_deref(), Some("USB Audio"));
+    }
+}
+
+#[cfg(test)]
+mod replay_gain_tests {
+    use flac_music_player::music::format::AudioTags;
+    use flac_music_player::music::replaygain::ReplayGain;
+    use flac_music_player::settings::ReplayGainMode;
+
+    fn tags(fields: &[(&str, &str)]) -> AudioTags {
+        AudioTags {
+            fields: fields.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_read_replay_gain_and_r128_tags() {
+        let replay_gain = ReplayGain::from_tags(&tags(&[
+            ("REPLAYGAIN_TRACK_GAIN", "-6.50 dB"),
+            ("REPLAYGAIN_TRACK_PEAK", "0.988"),
+            ("R128_ALBUM_GAIN", "-512"),
+        ]));
+        assert_eq!(replay_gain.track_gain_db, Some(-6.5));
+        assert_eq!(replay_gain.track_peak, Some(0.988));
+        // R128（-23 LUFS基準）の-2dBはReplayGain基準で+3dB
+        assert_eq!(replay_gain.album_gain_db, Some(3.0));
+        assert_eq!(replay_gain.album_peak, None);
+
+        assert_eq!(ReplayGain::from_tags(&tags(&[])), ReplayGain::default());
+    }
+
+    #[test]
+    fn test_playback_gain_modes_preamp_and_clipping() {
+        let replay_gain = ReplayGain {
+            track_gain_db: Some(-3.0),
+            track_peak: Some(0.5),
+            album_gain_db: Some(8.0),
+            album_peak: Some(0.5),
+        };
+        assert_eq!(replay_gain.playback_gain_db(ReplayGainMode::Off, 6.0, true), 0.0);
+        assert_eq!(replay_gain.playback_gain_db(ReplayGainMode::Track, 2.0, true), -1.0);
+
+        // ピーク0.5は約+6.02dBまでしかクリップしない
+        let album = replay_gain.playback_gain_db(ReplayGainMode::Album, 0.0, true);
+        assert!((album - 6.0206).abs() < 0.001);
+        assert_eq!(replay_gain.playback_gain_db(ReplayGainMode::Album, 0.0, false), 8.0);
+
+        // アルバムゲインが無い場合はトラックゲインを使い、ゲイン情報が無い場合は補正しない
+        let track_only = ReplayGain { album_gain_db: None, album_peak: None, ..replay_gain };
+        assert_eq!(track_only.playback_gain_db(ReplayGainMode::Album, 0.0, true), -3.0);
+        assert_eq!(ReplayGain::default().playback_gain_db(ReplayGainMode::Track, 6.0, true), 0.0);
+    }
+}
+
+#[cfg(test)]
+mod loudness_tests {
+    use flac_music_player::music::loudness::{album_loudness, LoudnessMeter};
+    use flac_music_player::music::replaygain::ReplayGain;
+
+    fn measure_sine(amplitude: f32, seconds: usize) -> flac_music_player::music::loudness::TrackLoudness {
+        let sample_rate = 48_000;
+        let samples: Vec<f32> = (0..sample_rate * seconds)
+            .flat_map(|i| {
+                let value = amplitude * (2.0 * std::f32::consts::PI * 997.0 * i as f32 / sample_rate as f32).sin();
+                [value, value]
+            })
+            .collect();
+
+        let mut meter = LoudnessMeter::new(sample_rate as u32, 2);
+        meter.process_interleaved(&samples);
+        meter.finish()
+    }
+
+    #[test]
+    fn test_sine_loudness_and_true_peak() {
+        // -20dBFSのステレオ正弦波は約-20 LUFS
+        let loudness = measure_sine(0.1, 5);
+        let lufs = loudness.integrated_lufs().unwrap();
+        assert!((lufs + 20.0).abs() < 0.1, "lufs = {}", lufs);
+        assert!((loudness.true_peak - 0.1).abs() < 0.002, "peak = {}", loudness.true_peak);
+
+        let replay_gain = ReplayGain::from_loudness(Some(lufs), loudness.true_peak, None, 0.0);
+        assert!((replay_gain.track_gain_db.unwrap() - 2.0).abs() < 0.1);
+        assert_eq!(replay_gain.album_gain_db, None);
+    }
+
+    #[test]
+    fn test_silence_has_no_loudness_and_album_gating() {
+        assert!(measure_sine(0.0, 2).integrated_lufs().is_none());
+
+        let loud = measure_sine(0.1, 3);
+        let medium = measure_sine(0.05, 3);
+        let quiet = measure_sine(0.01, 3);
+
+        // アルバムは全曲のブロックをまとめてエネルギー平均（-20と-26 LUFSで約-22 LUFS）
+        let (album_lufs, album_peak) = album_loudness(&[&loud, &medium]);
+        let album_lufs = album_lufs.unwrap();
+        assert!((album_lufs + 22.04).abs() < 0.1, "album = {}", album_lufs);
+        assert!((album_peak - 0.1).abs() < 0.002);
+
+        // -40 LUFSの曲は相対ゲート（約-32.6 LUFS）で除外される
+        let (gated_lufs, _) = album_loudness(&[&loud, &quiet]);
+        assert!((gated_lufs.unwrap() + 20.0).abs() < 0.1);
+    }
+}
+
+#[cfg(test)]
+mod ab_loop_tests {
+    use flac_music_player::player::{AbLoop, AbLoopPoint};
+    use std::time::Duration;
+
+    #[test]
+    fn test_region_requires_both_points_in_order() {
+        let mut ab_loop = AbLoop::new();
+        assert!(ab_loop.is_empty());
+
+        ab_loop.set_point(AbLoopPoint::A, Duration::from_secs(30));
+        assert_eq!(ab_loop.region(), None);
+
+        // 開始点より前に終了点を指定した場合は入れ替える
+        ab_loop.set_point(AbLoopPoint::B, Duration::from_secs(10));
+        assert_eq!(ab_loop.region(), Some((Duration::from_secs(10), Duration::from_secs(30))));
+        assert!(ab_loop.contains(Duration::from_secs(10)));
+        assert!(!ab_loop.contains(Duration::from_secs(30)));
+
+        ab_loop.clear();
+        assert!(ab_loop.is_empty());
+    }
+
+    #[test]
+    fn test_too_short_region_does_not_loop() {
+        let mut ab_loop = AbLoop::new();
+        ab_loop.set_point(AbLoopPoint::A, Duration::from_millis(5000));
+        ab_loop.set_point(AbLoopPoint::B, Duration::from_millis(5050));
+        assert_eq!(ab_loop.region(), None);
+        assert!(!ab_loop.contains(Duration::from_millis(5010)));
+    }
+}
+
+#[cfg(test)]
+mod headless_playback_tests {
+    use super::*;
+    use flac_music_player::music::TrackInfo;
+    use flac_music_player::player::{AudioPlayer, PlaybackState};
+    use flac_music_player::settings::RepeatMode;
+    use std::path::Path;
+    use std::time::Duration;
+
+    /// 指定した長さの無音WAV（44.1kHz・16bit・モノラル）を生成
+    fn write_silent_wav(path: &Path, seconds: f64) {
+        let sample_rate: u32 = 44_100;
+        let samples = vec![0u8; (sample_rate as f64 * seconds) as usize * 2];
+
+        let mut fmt = Vec::new();
+        fmt.extend_from_slice(&1u16.to_le_bytes());               // PCM
+        fmt.extend_from_slice(&1u16.to_le_bytes());               // モノラル
+        fmt.extend_from_slice(&sample_rate.to_le_bytes());
+        fmt.extend_from_slice(&(sample_rate * 2).to_le_bytes());  // バイトレート
+        fmt.extend_from_slice(&2u16.to_le_bytes());               // ブロックサイズ
+        fmt.extend_from_slice(&16u16.to_le_bytes());              // ビット深度
+
+        let mut body = b"WAVE".to_vec();
+        for (id, chunk) in [(b"fmt ", &fmt), (b"data", &samples)] {
+            body.extend_from_slice(id);
+            body.extend_from_slice(&(chunk.len() as u32).to_le_bytes());
+            body.extend_from_slice(chunk);
+        }
+
+        let mut wav = b"RIFF".to_vec();
+        wav.extend_from_slice(&(body.len() as u32).to_le_bytes());
+        wav.extend(body);
+        std::fs::write(path, wav).unwrap();
+    }
+
+    fn create_track(dir: &Path, name: &str, seconds: f64) -> TrackInfo {
+        let path = dir.join(format!("{}.wav", name));
+        write_silent_wav(&path, seconds);
+        TrackInfo {
+            title: name.to_string(),
+            artist: "Test Artist".to_string(),
+            album_artist: None,
+            album: "Test Album".to_string(),
+            composer: None,
+            genre: None,
+            track_number: None,
+            track_total: None,
+            disc_number: None,
+            disc_total: None,
+            date: None,
+            path,
+            replay_gain: None,
+        }
+    }
+
+    /// UIの更新と同じように、少しずつ時間を進めながら再生位置を読み取る
+    fn advance_with_frames(player: &mut AudioPlayer, duration: Duration) {
+        let frame = Duration::from_millis(50);
+        let mut elapsed = Duration::ZERO;
+        while elapsed < duration {
+            player.advance_headless(frame);
+            player.get_playback_position();
+            elapsed += frame;
+        }
+    }
+
+    fn assert_position_near(player: &AudioPlayer, expected_seconds: f64) {
+        let position = player.get_playback_position().as_secs_f64();
+        assert!((position - expected_seconds).abs() < 0.05, "position {} != {}", position, expected_seconds);
+    }
+
+    #[test]
+    fn test_position_advances_with_virtual_time() {
+        let dir = TempDir::new("headless_playback_position");
+        let mut player = AudioPlayer::new_headless();
+        player.play(create_track(&dir, "a", 3.0)).unwrap();
+
+        player.advance_headless(Duration::from_millis(500));
+        assert_position_near(&player, 0.5);
+
+        // 一時停止中は時間を進めても位置が変わらない
+        player.pause();
+        player.advance_headless(Duration::from_millis(100));
+        let paused_position = player.get_playback_position();
+        player.advance_headless(Duration::from_millis(500));
+        assert_eq!(player.get_playback_position(), paused_position);
+    }
+
+    #[test]
+    fn test_seek_forward_stops_at_end_of_track() {
+        let dir = TempDir::new("headless_playback_seek_forward");
+        let mut player = AudioPlayer::new_headless();
+        player.play(create_track(&dir, "a", 3.0)).unwrap();
+        player.advance_headless(Duration::from_millis(500));
+
+        // シーク先はエンジンに反映されるまでの間も再生位置として報告される
+        player.seek_forward(1).unwrap();
+        assert_position_near(&player, 1.5);
+        advance_with_frames(&mut player, Duration::from_millis(600));
+        let position = player.get_playback_position().as_secs_f64();
+        assert!(position > 1.5 && position < 2.2, "position {}", position);
+        assert_eq!(*player.get_state(), PlaybackState::Playing);
+
+        // 楽曲の長さを超えるシークは停止になる
+        player.seek_forward(5).unwrap();
+        assert_eq!(*player.get_state(), PlaybackState::Stopped);
+        assert!(player.get_current_track().is_none());
+        assert!(player.is_finished());
+    }
+
+    #[test]
+    fn test_seek_to_position_at_or_past_end_stops() {
+        let dir = TempDir::new("headless_playback_seek_to_position");
+        let mut player = AudioPlayer::new_headless();
+        let track = create_track(&dir, "a", 2.0);
+
+        player.play(track.clone()).unwrap();
+        player.seek_to_position(Duration::from_secs(1)).unwrap();
+        assert_position_near(&player, 1.0);
+        assert_eq!(*player.get_state(), PlaybackState::Playing);
+
+        player.seek_to_position(player.get_total_duration().unwrap()).unwrap();
+        assert_eq!(*player.get_state(), PlaybackState::Stopped);
+
+        player.play(track).unwrap();
+        player.seek_to_position(Duration::from_secs(10)).unwrap();
+        assert_eq!(*player.get_state(), PlaybackState::Stopped);
+        assert_eq!(player.get_playback_position(), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_is_finished_after_track_end() {
+        let dir = TempDir::new("headless_playback_finished");
+        let mut player = AudioPlayer::new_headless();
+        player.play(create_track(&dir, "a", 1.0)).unwrap();
+
+        player.advance_headless(Duration::from_millis(500));
+        assert!(!player.is_finished());
+
+        // 楽曲の終了はエンジンが検知し、次の楽曲への切り替えはアプリ側が行う
+        player.advance_headless(Duration::from_millis(700));
+        assert!(player.is_finished());
+        assert_eq!(*player.get_state(), PlaybackState::Playing);
+    }
+
+    #[test]
+    fn test_check_playback_finished_advances_to_next_track() {
+        let dir = TempDir::new("headless_playback_auto_advance");
+        let mut app = MyApp::new();
+        app.player_state.audio_player = AudioPlayer::new_headless();
+        app.player_state.repeat_mode = RepeatMode::Normal;
+        app.player_state.shuffle_enabled = false;
+
+        let playlist_id = app.playlist_manager.create_playlist("Headless".to_string());
+        app.playlist_manager.set_active_playlist(&playlist_id);
+        let first = create_track(&dir, "first", 1.0);
+        let second = create_track(&dir, "second", 1.0);
+        app.playlist_manager.add_track(first.clone());
+        app.playlist_manager.add_track(second.clone());
+
+        app.playlist_manager.set_current_playing_index(Some(0));
+        app.player_state.audio_player.play(first).unwrap();
+
+        // UIの更新と同じように、少しずつ時間を進めながら終了チェックを行う
+        for _ in 0..15 {
+            app.player_state.audio_player.advance_headless(Duration::from_millis(100));
+            app.check_playback_finished();
+        }
+        assert_eq!(app.playlist_manager.get_current_playing_index(), Some(1));
+        let playing = app.player_state.audio_player.get_current_track().map(|track| track.path.clone());
+        assert_eq!(playing, Some(second.path));
+        assert_eq!(*app.player_state.audio_player.get_state(), PlaybackState::Playing);
+        let position = app.player_state.audio_player.get_playback_position().as_secs_f64();
+        assert!(position > 0.3 && position < 0.7, "position {}", position);
+
+        // 最後の楽曲が終わると停止する
+        for _ in 0..10 {
+            app.player_state.audio_player.advance_headless(Duration::from_millis(100));
+            app.check_playback_finished();
+        }
+        assert_eq!(*app.player_state.audio_player.get_state(), PlaybackState::Stopped);
+        assert_eq!(app.playlist_manager.get_current_playing_index(), None);
+    }
+}